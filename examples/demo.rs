@@ -10,22 +10,95 @@ use carnot::{
 };
 use rand::seq::SliceRandom;
 use rand::thread_rng;
+use std::process::ExitCode;
+use std::sync::Arc;
+use winit::event::MouseButton;
+use winit::keyboard::KeyCode;
+use winit::window::Window;
 
-fn main() {
+/// Length of a round in seconds.
+const ROUND_LENGTH: f32 = 30.0;
+
+fn main() -> ExitCode {
     App::new()
         .with_title("Carnot Demo")
         .with_default_systems()
+        .with_state(GameState::Start)
         .with_system(Startup, initialize_player)
         .with_system(Startup, spawn_scene)
         .with_system(Startup, init_grid)
         .with_system(Startup, spawn_targets)
-        .with_system(Update, check_hit)
-        .run();
+        .with_system(OnEnter(GameState::Start), show_start_screen)
+        .with_system(OnEnter(GameState::Playing), start_round)
+        .with_system(OnEnter(GameState::GameOver), show_game_over)
+        .with_system(Update, start_on_click.run_if(in_state(GameState::Start)))
+        .with_system(Update, start_on_click.run_if(in_state(GameState::GameOver)))
+        .with_system(FixedUpdate, tick_round.run_if(in_state(GameState::Playing)))
+        .with_system(Update, check_hit.run_if(in_state(GameState::Playing)))
+        .with_system(Update, exit_on_escape)
+        .run()
+}
+
+fn exit_on_escape(input: Res<InputState>, mut app_exit: ResMut<AppExit>) {
+    if input.key_codes.just_pressed(KeyCode::Escape) {
+        app_exit.exit();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GameState {
+    Start,
+    Playing,
+    GameOver,
+}
+
+struct Round {
+    remaining: f32,
+    score: u32,
+}
+
+fn show_start_screen(window: Res<Arc<Window>>) {
+    window.set_title("Carnot Demo - Click to start");
+}
+
+fn start_on_click(input: Res<InputState>, mut next_state: ResMut<NextState<GameState>>) {
+    if input.mouse_buttons.just_pressed(MouseButton::Left) {
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn start_round(world: &mut World) {
+    world.add_resource(Round {
+        remaining: ROUND_LENGTH,
+        score: 0,
+    });
+    world
+        .get_resource::<Arc<Window>>()
+        .unwrap()
+        .set_title("Carnot Demo - Score: 0");
+}
+
+fn tick_round(
+    fixed_time: Res<FixedTime>,
+    mut round: ResMut<Round>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    round.remaining -= fixed_time.delta_seconds();
+    if round.remaining <= 0.0 {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn show_game_over(window: Res<Arc<Window>>, round: Res<Round>) {
+    window.set_title(&format!(
+        "Carnot Demo - Game over! Score: {} - Click to play again",
+        round.score
+    ));
 }
 
 fn initialize_player(mut query: Query<(&mut CameraTransform, &ActiveCamera)>) {
     for (transform, _) in &mut query {
-        transform.0.w_axis = Vec4::new(0.0, 1.8, 0.0, 0.0);
+        transform.translation = Vec3::new(0.0, 1.8, 0.0);
     }
 }
 
@@ -44,11 +117,7 @@ fn spawn_scene(world: &mut World) {
     world.add_component_to_entity::<LambertMaterial>(floor, material);
     world.add_component_to_entity::<Transform>(
         floor,
-        Transform::from(Mat4::from_scale_rotation_translation(
-            Vec3::new(x, 1.0, z),
-            glam::Quat::IDENTITY,
-            Vec3::new(0.0, 0.0, 0.0),
-        )),
+        Transform::from_translation(Vec3::new(0.0, 0.0, 0.0)).with_scale(Vec3::new(x, 1.0, z)),
     );
 
     let ceiling = world.new_entity();
@@ -56,11 +125,9 @@ fn spawn_scene(world: &mut World) {
     world.add_component_to_entity::<LambertMaterial>(ceiling, material);
     world.add_component_to_entity::<Transform>(
         ceiling,
-        Transform::from(Mat4::from_scale_rotation_translation(
-            Vec3::new(x, 1.0, z),
-            glam::Quat::from_rotation_x(std::f32::consts::PI),
-            Vec3::new(0.0, y, 0.0),
-        )),
+        Transform::from_translation(Vec3::new(0.0, y, 0.0))
+            .with_rotation(glam::Quat::from_rotation_x(std::f32::consts::PI))
+            .with_scale(Vec3::new(x, 1.0, z)),
     );
 
     let left_wall = world.new_entity();
@@ -68,11 +135,9 @@ fn spawn_scene(world: &mut World) {
     world.add_component_to_entity::<LambertMaterial>(left_wall, material);
     world.add_component_to_entity::<Transform>(
         left_wall,
-        Transform::from(Mat4::from_scale_rotation_translation(
-            Vec3::new(y, 1.0, z),
-            glam::Quat::from_rotation_z(-std::f32::consts::PI / 2.0),
-            Vec3::new(-x / 2.0, y / 2.0, 0.0),
-        )),
+        Transform::from_translation(Vec3::new(-x / 2.0, y / 2.0, 0.0))
+            .with_rotation(glam::Quat::from_rotation_z(-std::f32::consts::PI / 2.0))
+            .with_scale(Vec3::new(y, 1.0, z)),
     );
 
     let right_wall = world.new_entity();
@@ -80,11 +145,9 @@ fn spawn_scene(world: &mut World) {
     world.add_component_to_entity::<LambertMaterial>(right_wall, material);
     world.add_component_to_entity::<Transform>(
         right_wall,
-        Transform::from(Mat4::from_scale_rotation_translation(
-            Vec3::new(y, 1.0, z),
-            glam::Quat::from_rotation_z(std::f32::consts::PI / 2.0),
-            Vec3::new(x / 2.0, y / 2.0, 0.0),
-        )),
+        Transform::from_translation(Vec3::new(x / 2.0, y / 2.0, 0.0))
+            .with_rotation(glam::Quat::from_rotation_z(std::f32::consts::PI / 2.0))
+            .with_scale(Vec3::new(y, 1.0, z)),
     );
 
     let back_wall = world.new_entity();
@@ -92,11 +155,9 @@ fn spawn_scene(world: &mut World) {
     world.add_component_to_entity::<LambertMaterial>(back_wall, material);
     world.add_component_to_entity::<Transform>(
         back_wall,
-        Transform::from(Mat4::from_scale_rotation_translation(
-            Vec3::new(x, 1.0, y),
-            glam::Quat::from_rotation_x(-std::f32::consts::PI / 2.0),
-            Vec3::new(0.0, y / 2.0, z / 2.0),
-        )),
+        Transform::from_translation(Vec3::new(0.0, y / 2.0, z / 2.0))
+            .with_rotation(glam::Quat::from_rotation_x(-std::f32::consts::PI / 2.0))
+            .with_scale(Vec3::new(x, 1.0, y)),
     );
 
     let front_wall = world.new_entity();
@@ -104,11 +165,9 @@ fn spawn_scene(world: &mut World) {
     world.add_component_to_entity::<LambertMaterial>(front_wall, material);
     world.add_component_to_entity::<Transform>(
         front_wall,
-        Transform::from(Mat4::from_scale_rotation_translation(
-            Vec3::new(x, 1.0, y),
-            glam::Quat::from_rotation_x(std::f32::consts::PI / 2.0),
-            Vec3::new(0.0, y / 2.0, -z / 2.0),
-        )),
+        Transform::from_translation(Vec3::new(0.0, y / 2.0, -z / 2.0))
+            .with_rotation(glam::Quat::from_rotation_x(std::f32::consts::PI / 2.0))
+            .with_scale(Vec3::new(x, 1.0, y)),
     );
 }
 
@@ -139,11 +198,7 @@ fn spawn_targets(world: &mut World) {
         world.add_component_to_entity::<ImplicitSphere>(sphere, implicit);
         world.add_component_to_entity::<Transform>(
             sphere,
-            Transform::from(Mat4::from_scale_rotation_translation(
-                Vec3::splat(0.3),
-                Quat::IDENTITY,
-                location,
-            )),
+            Transform::from_translation(location).with_scale(Vec3::splat(0.3)),
         );
         world.add_component_to_entity::<GridIndex>(sphere, grid_index);
         world.add_component_to_entity::<Target>(sphere, Target);
@@ -154,26 +209,24 @@ fn check_hit(
     camera: Query<(&CameraTransform, &ActiveCamera)>,
     mut targets: Query<(&mut Transform, &ImplicitSphere, &mut GridIndex, &Target)>,
     mut target_grid: ResMut<TargetGrid>,
-    input: ResMut<InputState>,
+    (input, mut round, window): (Res<InputState>, ResMut<Round>, Res<Arc<Window>>),
 ) {
-    if !input.clicked {
+    if !input.mouse_buttons.just_pressed(MouseButton::Left) {
         return;
     }
-    let camera_mat = camera.into_iter().next().unwrap().0 .0;
-    let camera_pos = camera_mat.w_axis.truncate();
-    let camera_look = -camera_mat.z_axis.truncate();
+    let (camera_transform, _) = camera.into_iter().next().unwrap();
+    let camera_pos = camera_transform.translation;
+    let camera_look = camera_transform.forward();
 
     for (target_transform, target_implicit, grid_index, _) in &mut targets {
         let ray = Ray::new(camera_pos, camera_look);
         let hit = target_implicit.intersect_world(&ray, target_transform);
         if hit.is_some() {
             let (new_location, new_index) = target_grid.move_target(grid_index).unwrap();
-            target_transform.0 = Mat4::from_scale_rotation_translation(
-                Vec3::splat(0.3),
-                Quat::IDENTITY,
-                new_location,
-            );
+            target_transform.translation = new_location;
             *grid_index = new_index;
+            round.score += 1;
+            window.set_title(&format!("Carnot Demo - Score: {}", round.score));
         }
     }
 }
@@ -1,51 +1,152 @@
-use crate::builtins::systems::camera::{init_camera_system, update_camera_system};
+use crate::builtins::resources::time::{FixedTime, InterpolationAlpha, Time};
+use crate::builtins::systems::camera::{
+    init_camera_system, update_camera_path_system, update_camera_system, FlyCameraAction,
+};
+use crate::builtins::systems::hierarchy::propagate_transforms_system;
 use crate::builtins::systems::render::{
-    init_crosshair_pipeline_system, init_pipeline_system, init_renderer_system,
-    update_render_system,
+    check_mesh_system, init_crosshair_pipeline_system, init_pipeline_system, init_renderer_system,
+    update_camera_projection_system, update_render_system,
 };
-use crate::builtins::systems::ActiveCamera;
-use crate::ecs::query::Query;
+use crate::builtins::systems::visibility::update_visibility_system;
 use crate::ecs::resource::ResMut;
-use crate::ecs::system::{BoxedSystem, IntoSystem, System, SystemOrWorldParam, SystemParam};
+use crate::ecs::state::{
+    NextState, OnEnter, OnExit, State, StateSchedules, StateTransition, States,
+};
+use crate::ecs::system::{
+    error, run_systems, BoxedSystem, ErrorHandler, IntoSystem, System, SystemOrWorldParam,
+};
 use crate::ecs::World;
-use crate::graphics::PerspectiveCamera;
-use crate::input::InputState;
-use crate::render::Renderer;
+use crate::input::input_map::update_input_map_system;
+use crate::input::recording::{InputEvent, InputRecorder, InputReplay};
+use crate::input::{Action, InputMap, InputState, TextInputEvent, Touches};
+use crate::render::{DepthMode, Renderer};
+use std::any::TypeId;
+use std::process::ExitCode;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use winit::{
     application::ApplicationHandler,
     event::WindowEvent,
     event_loop::ControlFlow,
     event_loop::{ActiveEventLoop, EventLoop},
+    keyboard::PhysicalKey,
     window::Window,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SystemStage {
     Startup,
+    /// Runs every frame before [`FixedUpdate`] and [`Update`], e.g. to process input.
+    PreUpdate,
+    /// Runs zero or more times per frame at the rate set by the [`FixedTime`] resource, before
+    /// [`Update`].
+    FixedUpdate,
     Update,
+    /// Runs once when the app exits, before the [`Renderer`] and window are dropped.
+    Shutdown,
 }
 
 use SystemStage::*;
 
+/// Resource that any system can use to request that the app exits.
+///
+/// The request is handled once the current frame's systems have run. The exit code is returned
+/// from [`App::run`].
+#[derive(Debug, Default)]
+pub struct AppExit {
+    code: Option<u8>,
+}
+
+impl AppExit {
+    /// Requests a successful exit.
+    pub fn exit(&mut self) {
+        self.exit_with_code(0);
+    }
+
+    /// Requests an exit with the given exit code.
+    pub fn exit_with_code(&mut self, code: u8) {
+        self.code = Some(code);
+    }
+
+    /// The requested exit code, if an exit has been requested.
+    pub fn requested(&self) -> Option<u8> {
+        self.code
+    }
+}
+
+/// Anything a system can be added to with [`App::with_system`]: a [`SystemStage`], or the
+/// [`OnEnter`]/[`OnExit`] schedule of a state.
+pub trait Stage {
+    fn add_system(self, app: &mut App, system: BoxedSystem);
+}
+
+impl Stage for SystemStage {
+    fn add_system(self, app: &mut App, system: BoxedSystem) {
+        match self {
+            Startup => app.startup_systems.push(system),
+            PreUpdate => app.pre_update_systems.push(system),
+            FixedUpdate => app.fixed_systems.push(system),
+            Update => app.systems.push(system),
+            Shutdown => app.shutdown_systems.push(system),
+        }
+    }
+}
+
+impl<S: States> Stage for OnEnter<S> {
+    fn add_system(self, app: &mut App, system: BoxedSystem) {
+        app.state_schedules_mut::<S>()
+            .on_enter
+            .push((self.0, system));
+    }
+}
+
+impl<S: States> Stage for OnExit<S> {
+    fn add_system(self, app: &mut App, system: BoxedSystem) {
+        app.state_schedules_mut::<S>()
+            .on_exit
+            .push((self.0, system));
+    }
+}
+
 pub struct App {
     title: String,
+    lock_cursor: bool,
+    ime_allowed: bool,
     pub window: Option<Arc<Window>>,
     pub world: World,
     pub startup_systems: Vec<BoxedSystem>,
+    pub pre_update_systems: Vec<BoxedSystem>,
+    pub fixed_systems: Vec<BoxedSystem>,
     pub systems: Vec<BoxedSystem>,
+    pub shutdown_systems: Vec<BoxedSystem>,
+    state_transitions: Vec<(TypeId, Box<dyn StateTransition>)>,
+    error_handler: ErrorHandler,
+    last_frame: Option<Instant>,
 }
 
 impl Default for App {
     fn default() -> Self {
         let mut world = World::new();
         world.add_resource(InputState::default());
+        world.add_resource(Touches::default());
+        world.add_resource(AppExit::default());
+        world.add_resource(Time::default());
+        world.add_resource(FixedTime::default());
+        world.add_resource(InterpolationAlpha::default());
         Self {
             title: "Carnot Application".to_string(),
+            lock_cursor: true,
+            ime_allowed: false,
             world,
             window: Default::default(),
             startup_systems: Default::default(),
+            pre_update_systems: Default::default(),
+            fixed_systems: Default::default(),
             systems: Default::default(),
+            shutdown_systems: Default::default(),
+            state_transitions: Default::default(),
+            error_handler: error::log,
+            last_frame: None,
         }
     }
 }
@@ -60,14 +161,94 @@ impl App {
         self
     }
 
+    /// Sets whether the cursor is hidden and locked to the window, as needed for mouse look.
+    /// Defaults to `true`; disable it to use the cursor for e.g. [picking](InputState::cursor_ray).
+    pub fn with_cursor_locked(mut self, lock_cursor: bool) -> Self {
+        self.lock_cursor = lock_cursor;
+        self
+    }
+
+    /// Sets whether the window accepts input from an input method editor, which delivers composed
+    /// text as [`TextInputEvent::Preedit`] and [`TextInputEvent::Commit`]. Defaults to `false`.
+    ///
+    /// It can also be toggled at runtime with [`Window::set_ime_allowed`], e.g. while a text field
+    /// is focused.
+    pub fn with_ime_allowed(mut self, ime_allowed: bool) -> Self {
+        self.ime_allowed = ime_allowed;
+        self
+    }
+
+    /// Sets whether the primary touch on a touchscreen also acts as the mouse, so that mouse-driven
+    /// systems work unchanged. Defaults to `false`.
+    pub fn with_touch_emulation(self, emulate_mouse: bool) -> Self {
+        self.world
+            .get_resource_mut::<Touches>()
+            .unwrap()
+            .emulate_mouse = emulate_mouse;
+        self
+    }
+
+    /// Sets how depth is mapped by the renderer and every camera. Defaults to
+    /// [`DepthMode::Standard`]; use [`DepthMode::ReverseZ`] for large scenes.
+    pub fn with_depth_mode(mut self, depth_mode: DepthMode) -> Self {
+        self.world.add_resource(depth_mode);
+        self
+    }
+
+    /// Replaces the [`FixedTime`] resource that drives the [`FixedUpdate`] stage.
+    pub fn with_fixed_time(mut self, fixed_time: FixedTime) -> Self {
+        self.world.add_resource(fixed_time);
+        self
+    }
+
+    /// Sets the handler for errors returned by systems, e.g. [`error::log`] (the default),
+    /// [`error::panic`] or [`error::ignore`].
+    pub fn with_error_handler(mut self, handler: ErrorHandler) -> Self {
+        self.error_handler = handler;
+        self
+    }
+
+    /// Adds an [`InputMap`] resource for the actions `A`, updated from the [`InputState`] at the
+    /// start of every frame.
+    pub fn with_input_map<A: Action>(mut self, input_map: InputMap<A>) -> Self {
+        self.world.add_resource(input_map);
+        self.with_system(PreUpdate, update_input_map_system::<A>)
+    }
+
+    /// Records all input to a file, which is written when the app exits.
+    pub fn with_input_recording(mut self, recorder: InputRecorder) -> Self {
+        self.world.add_resource(recorder);
+        self
+    }
+
+    /// Plays back a recording instead of live input. The app exits once the recording ends.
+    pub fn with_input_replay(mut self, replay: InputReplay) -> Self {
+        self.world.add_resource(replay);
+        self
+    }
+
+    /// Adds the state `S` to the app, starting in `initial`.
+    ///
+    /// This inserts the [`State<S>`] and [`NextState<S>`] resources. Transitions requested through
+    /// [`NextState::set`] are applied at the end of the frame, running the [`OnExit`] systems of
+    /// the old state followed by the [`OnEnter`] systems of the new one.
+    pub fn with_state<S: States>(mut self, initial: S) -> Self {
+        self.world.add_resource(State(initial));
+        self.world.add_resource(NextState::<S>(None));
+        self.state_schedules_mut::<S>();
+        self
+    }
+
     /// Adds the default systems to the application.
     ///
     /// The default systems are:
     /// - [`init_camera_system`]
     ///   - Provides a camera entity centered at origin looking down -Z composed of:
-    ///     - [`PerspectiveCamera`]
+    ///     - [`Camera`](crate::graphics::Camera) drawing to the whole window
+    ///     - [`Projection`](crate::graphics::Projection) with a [`PerspectiveCamera`](crate::graphics::PerspectiveCamera)
     ///     - [`CameraTransform`](crate::graphics::camera::CameraTransform)
     ///     - [`ActiveCamera`](crate::builtins::systems::ActiveCamera)
+    ///     - [`FlyCamera`](crate::builtins::systems::camera::FlyCamera)
     /// - [`init_renderer_system`]
     ///   - Initializes the rendering context and creates a [`Renderer`] resource
     /// - [`init_pipeline_system`]
@@ -75,34 +256,231 @@ impl App {
     /// - [`init_crosshair_pipeline_system`]
     ///   - Initializes the crosshair rendering pipeline which renders a crosshair at the center of the screen
     /// - [`update_camera_system`]
-    ///   - Updates the camera position based on user input
+    ///   - Moves the camera according to its [`FlyCamera`](crate::builtins::systems::camera::FlyCamera)
+    ///     settings and the user input mapped through an [`InputMap<FlyCameraAction>`](InputMap),
+    ///     which defaults to WASD, space/control, shift to sprint and mouse look
+    /// - [`update_camera_path_system`]
+    ///   - Moves cameras along their playing
+    ///     [`CameraPath`](crate::builtins::systems::camera::CameraPath), overriding the fly camera
+    /// - [`propagate_transforms_system`]
+    ///   - Moves entities with a [`Parent`](crate::builtins::systems::hierarchy::Parent) along with
+    ///     it
+    /// - [`update_camera_projection_system`]
+    ///   - Matches the [`Projection`](crate::graphics::Projection) of every camera to its viewport
+    ///     size and the [`DepthMode`]
+    /// - [`update_visibility_system`]
+    ///   - Culls entities outside the view frustum of every camera, marking them with a
+    ///     [`Visibility`](crate::builtins::systems::visibility::Visibility) and counting them in
+    ///     [`CullingStats`](crate::builtins::systems::visibility::CullingStats)
+    /// - [`check_mesh_system`]
+    ///   - Checks each new [`Mesh`](crate::graphics::Mesh) once, reporting entities whose mesh
+    ///     cannot be drawn so that the render system skips them
     /// - [`update_render_system`]
-    ///   - Renders all entities with a renders all entities with a [`Mesh`](crate::graphics::Mesh) and [`Transform`](crate::graphics::Transform) component using the [`ActiveCamera`](crate::builtins::systems::ActiveCamera) camera.
+    ///   - Renders all entities with a renders all entities with a [`Mesh`](crate::graphics::Mesh) and [`Transform`](crate::graphics::Transform) component that are not culled from every [`Camera`](crate::graphics::Camera), in order.
     pub fn with_default_systems(self) -> Self {
-        self.with_system(Startup, init_camera_system)
+        self.with_input_map(FlyCameraAction::default_input_map())
+            .with_system(Startup, init_camera_system)
             .with_system(Startup, init_renderer_system)
             .with_system(Startup, init_pipeline_system)
             .with_system(Startup, init_crosshair_pipeline_system)
             .with_system(Update, update_camera_system)
+            .with_system(Update, update_camera_path_system)
+            .with_system(Update, propagate_transforms_system)
+            .with_system(Update, update_camera_projection_system)
+            .with_system(Update, update_visibility_system)
+            .with_system(Update, check_mesh_system)
             .with_system(Update, update_render_system)
     }
 
     pub fn with_system<F: IntoSystem<M>, M: SystemOrWorldParam>(
         mut self,
-        stage: SystemStage,
+        stage: impl Stage,
         function: F,
     ) -> Self {
-        match stage {
-            Startup => self.startup_systems.push(Box::new(function.into_system())),
-            Update => self.systems.push(Box::new(function.into_system())),
-        }
+        stage.add_system(&mut self, Box::new(function.into_system()));
         self
     }
 
-    pub fn run(mut self) {
+    /// Runs the app until it exits, returning the exit code requested through [`AppExit`].
+    pub fn run(mut self) -> ExitCode {
         let event_loop = EventLoop::new().unwrap();
         event_loop.set_control_flow(ControlFlow::Poll);
-        let _ = event_loop.run_app(&mut self);
+        if let Err(e) = event_loop.run_app(&mut self) {
+            eprintln!("Event loop error: {}", e);
+            return ExitCode::FAILURE;
+        }
+        let code = self
+            .world
+            .get_resource::<AppExit>()
+            .and_then(|app_exit| app_exit.requested())
+            .unwrap_or(0);
+        ExitCode::from(code)
+    }
+
+    /// Runs the app without a window or event loop until an exit is requested through
+    /// [`AppExit`], e.g. to play back an [`InputReplay`] in a test.
+    ///
+    /// Systems that need a window or [`Renderer`], such as the
+    /// [default systems](App::with_default_systems), cannot be used.
+    pub fn run_headless(mut self) -> ExitCode {
+        self.startup();
+        while self
+            .world
+            .get_resource::<AppExit>()
+            .unwrap()
+            .requested()
+            .is_none()
+        {
+            self.update();
+        }
+        self.shutdown();
+        ExitCode::from(
+            self.world
+                .get_resource::<AppExit>()
+                .unwrap()
+                .requested()
+                .unwrap(),
+        )
+    }
+
+    /// Runs the [`Startup`] systems and enters the initial states.
+    fn startup(&mut self) {
+        run_systems(
+            &mut self.startup_systems,
+            &mut self.world,
+            self.error_handler,
+        );
+        self.apply_state_transitions();
+    }
+
+    /// Runs a single frame.
+    pub fn update(&mut self) {
+        let delta = match self.world.get_resource_mut::<InputReplay>() {
+            Some(mut replay) => {
+                let mut input_state = self.world.get_resource_mut::<InputState>().unwrap();
+                let mut touches = self.world.get_resource_mut::<Touches>().unwrap();
+                match replay.begin_frame(&mut input_state, &mut touches) {
+                    Some(delta) => delta,
+                    None => {
+                        let code = replay.divergence().is_some() as u8;
+                        self.world
+                            .get_resource_mut::<AppExit>()
+                            .unwrap()
+                            .exit_with_code(code);
+                        return;
+                    }
+                }
+            }
+            None => {
+                let now = Instant::now();
+                let delta = self
+                    .last_frame
+                    .map(|last_frame| now - last_frame)
+                    .unwrap_or_default();
+                self.last_frame = Some(now);
+                delta
+            }
+        };
+
+        self.world.get_resource_mut::<Time>().unwrap().0 = delta.as_secs_f64();
+
+        run_systems(
+            &mut self.pre_update_systems,
+            &mut self.world,
+            self.error_handler,
+        );
+        self.run_fixed_systems(delta);
+        run_systems(&mut self.systems, &mut self.world, self.error_handler);
+        self.apply_state_transitions();
+
+        if let Some(mut recorder) = self.world.get_resource_mut::<InputRecorder>() {
+            recorder.end_frame(delta, &self.world);
+        }
+        if let Some(mut replay) = self.world.get_resource_mut::<InputReplay>() {
+            replay.end_frame(&self.world);
+        }
+        self.world.get_resource_mut::<InputState>().unwrap().clear();
+        self.world.get_resource_mut::<Touches>().unwrap().clear();
+    }
+
+    /// Runs the [`Shutdown`] systems and saves any input recording.
+    fn shutdown(&mut self) {
+        run_systems(
+            &mut self.shutdown_systems,
+            &mut self.world,
+            self.error_handler,
+        );
+        if let Some(recorder) = self.world.get_resource::<InputRecorder>() {
+            if let Err(e) = recorder.save() {
+                eprintln!(
+                    "Failed to save input recording to {}: {}",
+                    recorder.path().display(),
+                    e
+                );
+            }
+        }
+    }
+
+    /// Applies a live input event to the [`InputState`] and [`Touches`], recording it if an [`InputRecorder`] is
+    /// present. Live input is ignored while an [`InputReplay`] is playing.
+    fn handle_input(&mut self, event: InputEvent) {
+        if self.world.get_resource::<InputReplay>().is_some() {
+            return;
+        }
+        if let Some(mut recorder) = self.world.get_resource_mut::<InputRecorder>() {
+            recorder.record(event.clone());
+        }
+        event.apply(
+            &mut self.world.get_resource_mut::<InputState>().unwrap(),
+            &mut self.world.get_resource_mut::<Touches>().unwrap(),
+        );
+    }
+}
+
+impl App {
+    fn state_schedules_mut<S: States>(&mut self) -> &mut StateSchedules<S> {
+        let type_id = TypeId::of::<S>();
+        let index = match self
+            .state_transitions
+            .iter()
+            .position(|(id, _)| *id == type_id)
+        {
+            Some(index) => index,
+            None => {
+                self.state_transitions
+                    .push((type_id, Box::new(StateSchedules::<S>::default())));
+                self.state_transitions.len() - 1
+            }
+        };
+        self.state_transitions[index]
+            .1
+            .as_any_mut()
+            .downcast_mut::<StateSchedules<S>>()
+            .expect("failed to downcast state schedules to StateSchedules<S>")
+    }
+
+    fn apply_state_transitions(&mut self) {
+        for (_, transition) in self.state_transitions.iter_mut() {
+            transition.apply(&mut self.world, self.error_handler);
+        }
+    }
+
+    /// Advances the [`FixedTime`] accumulator by `delta` and runs the [`FixedUpdate`] systems once
+    /// per whole step.
+    fn run_fixed_systems(&mut self, delta: Duration) {
+        self.world
+            .get_resource_mut::<FixedTime>()
+            .unwrap()
+            .accumulate(delta);
+        while self.world.get_resource_mut::<FixedTime>().unwrap().expend() {
+            run_systems(&mut self.fixed_systems, &mut self.world, self.error_handler);
+        }
+
+        let alpha = self.world.get_resource::<FixedTime>().unwrap().alpha();
+        self.world
+            .get_resource_mut::<InterpolationAlpha>()
+            .unwrap()
+            .0 = alpha;
     }
 }
 
@@ -112,29 +490,32 @@ impl ApplicationHandler for App {
             .with_title(self.title.clone())
             .with_fullscreen(Some(winit::window::Fullscreen::Borderless(None)));
         let window = Arc::new(event_loop.create_window(attributes).unwrap());
-        window.set_cursor_visible(false);
         let physical_size = window.inner_size();
-        let width = physical_size.width;
-        let height = physical_size.height;
-        window
-            .set_cursor_position(winit::dpi::PhysicalPosition::new(
-                width as f64 / 2.0,
-                height as f64 / 2.0,
-            ))
-            .unwrap_or_else(|e| {
-                eprintln!("Failed to set cursor position: {}", e);
-            });
-        window
-            .set_cursor_grab(winit::window::CursorGrabMode::Locked)
-            .unwrap_or_else(|e| {
-                eprintln!("Failed to lock cursor: {}", e);
-            });
+        self.handle_input(InputEvent::Resized(physical_size));
+        self.handle_input(InputEvent::ScaleFactorChanged(window.scale_factor()));
+        window.set_ime_allowed(self.ime_allowed);
+        if self.lock_cursor {
+            window.set_cursor_visible(false);
+            let width = physical_size.width;
+            let height = physical_size.height;
+            window
+                .set_cursor_position(winit::dpi::PhysicalPosition::new(
+                    width as f64 / 2.0,
+                    height as f64 / 2.0,
+                ))
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to set cursor position: {}", e);
+                });
+            window
+                .set_cursor_grab(winit::window::CursorGrabMode::Locked)
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to lock cursor: {}", e);
+                });
+        }
         self.window = Some(window);
         self.world
             .add_resource::<Arc<Window>>(self.window.clone().unwrap());
-        for system in self.startup_systems.iter_mut() {
-            system.run(&mut self.world);
-        }
+        self.startup();
     }
 
     fn window_event(
@@ -145,104 +526,109 @@ impl ApplicationHandler for App {
     ) {
         match event {
             WindowEvent::RedrawRequested => {
-                for system in self.systems.iter_mut() {
-                    system.run(&mut self.world);
-                }
-                self.world.get_resource_mut::<InputState>().unwrap().clicked = false;
-                (|renderer: ResMut<Renderer>| renderer.window.request_redraw())
+                self.update();
+                let _ = (|renderer: ResMut<Renderer>| renderer.window.request_redraw())
                     .into_system()
                     .run(&mut self.world);
             }
             WindowEvent::KeyboardInput { event, .. } => {
-                if event.state == winit::event::ElementState::Pressed {
-                    self.world
-                        .get_resource_mut::<InputState>()
-                        .unwrap()
-                        .keys
-                        .insert(event.logical_key);
-                } else {
-                    self.world
-                        .get_resource_mut::<InputState>()
-                        .unwrap()
-                        .keys
-                        .remove(&event.logical_key);
+                let key_code = match event.physical_key {
+                    PhysicalKey::Code(key_code) => Some(key_code),
+                    PhysicalKey::Unidentified(_) => None,
+                };
+                let pressed = event.state == winit::event::ElementState::Pressed;
+                if pressed {
+                    if let Some(text) = event
+                        .text
+                        .and_then(|text| TextInputEvent::from_key_text(&text))
+                    {
+                        self.handle_input(InputEvent::Text(text));
+                    }
                 }
+                self.handle_input(InputEvent::Key {
+                    logical_key: event.logical_key,
+                    key_code,
+                    pressed,
+                });
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.handle_input(InputEvent::ModifiersChanged(modifiers.state()));
             }
+            WindowEvent::Ime(ime) => self.handle_input(InputEvent::Text(ime.into())),
             WindowEvent::MouseInput {
                 device_id: _,
                 state,
                 button,
-            } => match state {
-                winit::event::ElementState::Pressed => {
-                    let mut input_state = self.world.get_resource_mut::<InputState>().unwrap();
-                    input_state.clicked = true;
-                    input_state.mouse_buttons.insert(button);
-                }
-                winit::event::ElementState::Released => {
-                    self.world
-                        .get_resource_mut::<InputState>()
-                        .unwrap()
-                        .mouse_buttons
-                        .remove(&button);
-                }
-            },
-            // WindowEvent::CursorMoved { position, .. } => {
-            //     self.world
-            //         .get_resource_mut::<InputState>()
-            //         .unwrap()
-            //         .last_mouse_position = Some(
-            //         self.world
-            //             .get_resource_mut::<InputState>()
-            //             .unwrap()
-            //             .mouse_position,
-            //     );
-            //     self.world
-            //         .get_resource_mut::<InputState>()
-            //         .unwrap()
-            //         .mouse_position = position;
-            // }
+            } => {
+                self.handle_input(InputEvent::MouseButton {
+                    button,
+                    pressed: state == winit::event::ElementState::Pressed,
+                });
+            }
+            WindowEvent::Focused(false) => self.handle_input(InputEvent::ReleaseAll),
+            WindowEvent::CursorMoved { position, .. } => {
+                self.handle_input(InputEvent::CursorMoved { position });
+            }
+            WindowEvent::CursorEntered { .. } => self.handle_input(InputEvent::CursorEntered),
+            WindowEvent::CursorLeft { .. } => self.handle_input(InputEvent::CursorLeft),
+            WindowEvent::Touch(touch) => self.handle_input(InputEvent::Touch {
+                id: touch.id,
+                phase: touch.phase,
+                position: touch.location,
+            }),
             WindowEvent::CloseRequested => {
-                // Close the window
+                let mut app_exit = self.world.get_resource_mut::<AppExit>().unwrap();
+                if app_exit.requested().is_none() {
+                    app_exit.exit();
+                }
                 event_loop.exit();
             }
             WindowEvent::Resized(physical_size) => {
+                self.handle_input(InputEvent::Resized(physical_size));
+                // Camera projections follow their viewports in the render system
                 self.world
                     .get_resource_mut::<Renderer>()
                     .unwrap()
                     .resize(physical_size);
-                let mut query =
-                    <Query<(&mut PerspectiveCamera, &ActiveCamera)> as SystemParam>::fetch(
-                        &self.world,
-                    );
-                if let Some((camera, _)) = (&mut query).into_iter().next() {
-                    camera.update_aspect_ratio(
-                        physical_size.width as f32 / physical_size.height as f32,
-                    );
-                }
             }
-            WindowEvent::ScaleFactorChanged { .. } => {
-                // Update the window size
-                // ...
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                // The new size is delivered in a following Resized event
+                self.handle_input(InputEvent::ScaleFactorChanged(scale_factor));
             }
             _ => {}
         }
     }
 
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let exit_requested = self
+            .world
+            .get_resource::<AppExit>()
+            .is_some_and(|app_exit| app_exit.requested().is_some());
+        if exit_requested {
+            event_loop.exit();
+        }
+    }
+
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        self.shutdown();
+        // Tear down the GPU resources before the window they were created for
+        self.world.remove_resource::<Renderer>();
+        self.world.remove_resource::<Arc<Window>>();
+        self.window = None;
+    }
+
     fn device_event(
         &mut self,
         _event_loop: &ActiveEventLoop,
         _device_id: winit::event::DeviceId,
         event: winit::event::DeviceEvent,
     ) {
-        let mut input_state = self.world.get_resource_mut::<InputState>().unwrap();
-
         match event {
             winit::event::DeviceEvent::MouseMotion { delta } => {
-                let current_delta = input_state.mouse_delta;
-                input_state.mouse_delta = (current_delta.0 + delta.0, current_delta.1 + delta.1);
+                self.handle_input(InputEvent::MouseMotion { delta });
             }
             winit::event::DeviceEvent::MouseWheel { delta } => {
-                input_state.mouse_scroll_delta = delta;
+                self.handle_input(InputEvent::MouseWheel { delta });
             }
             _ => {}
         }
@@ -0,0 +1,11 @@
+pub use export::{bake_world, export_world, save_mesh, ExportError, MeshFormat};
+pub use gltf::{BaseColorTexture, GltfError, GltfNodeInstance, GltfScene};
+pub use obj::{load_obj, write_obj, ObjError, ObjMaterial, ObjMesh, ObjParseError, ObjScene};
+pub use ply::{write_ply, PlyEncoding};
+pub use stl::write_stl;
+
+pub mod export;
+pub mod gltf;
+pub mod obj;
+pub mod ply;
+pub mod stl;
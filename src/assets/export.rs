@@ -0,0 +1,167 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use super::obj::write_obj;
+use super::ply::{write_ply, PlyEncoding};
+use super::stl::write_stl;
+use crate::builtins::systems::visibility::Visibility;
+use crate::ecs::World;
+use crate::graphics::mesh::{Mesh, MeshError};
+use crate::graphics::Transform;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Failed to write {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Nothing to export: the mesh has no vertices")]
+    Empty,
+    #[error(transparent)]
+    InvalidMesh(#[from] MeshError),
+}
+
+/// The file formats a [`Mesh`] can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshFormat {
+    Obj,
+    Ply(PlyEncoding),
+    /// Binary STL, e.g. for 3D printing.
+    Stl,
+}
+
+/// Checks that `mesh` can be drawn with [`Mesh::validate_drawable`] and writes it to `path`.
+/// Degenerate triangles are kept, but a mesh without vertices is rejected.
+pub fn save_mesh(
+    mesh: &Mesh,
+    path: impl AsRef<Path>,
+    format: MeshFormat,
+) -> Result<(), ExportError> {
+    if mesh.vertex_count() == 0 {
+        return Err(ExportError::Empty);
+    }
+    mesh.validate_drawable()?;
+    let path = path.as_ref();
+    let io_error = |source| ExportError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let writer = BufWriter::new(File::create(path).map_err(io_error)?);
+    match format {
+        MeshFormat::Obj => write_obj(mesh, writer),
+        MeshFormat::Ply(encoding) => write_ply(mesh, writer, encoding),
+        MeshFormat::Stl => write_stl(mesh, writer),
+    }
+    .map_err(io_error)
+}
+
+/// Merges the meshes of all entities with a [`Mesh`] and a [`Transform`] into one mesh in world
+/// space.
+///
+/// With `only_visible`, entities that [`update_visibility_system`] marked as
+/// [`Visibility::Culled`] are left out, so the result is what the cameras saw last frame.
+/// Otherwise every entity is included, whether or not it is in view.
+///
+/// [`update_visibility_system`]: crate::builtins::systems::visibility::update_visibility_system
+pub fn bake_world(world: &World, only_visible: bool) -> Mesh {
+    let mut baked = Mesh::default();
+    let (Some(meshes), Some(transforms)) = (
+        world.borrow_component_vec::<Mesh>(),
+        world.borrow_component_vec::<Transform>(),
+    ) else {
+        return baked;
+    };
+    let visibilities = world.borrow_component_vec::<Visibility>();
+    let culled = |entity: usize| {
+        only_visible
+            && visibilities
+                .as_ref()
+                .and_then(|visibilities| visibilities.get(entity))
+                .is_some_and(|visibility| *visibility == Some(Visibility::Culled))
+    };
+    for (entity, (mesh, transform)) in meshes.iter().zip(transforms.iter()).enumerate() {
+        if let (Some(mesh), Some(transform), false) = (mesh, transform, culled(entity)) {
+            baked.merge(mesh, transform);
+        }
+    }
+    baked
+}
+
+/// Bakes the meshes of `world` with [`bake_world`] and saves them to `path`.
+pub fn export_world(
+    world: &World,
+    path: impl AsRef<Path>,
+    format: MeshFormat,
+    only_visible: bool,
+) -> Result<(), ExportError> {
+    save_mesh(&bake_world(world, only_visible), path, format)
+}
+
+#[test]
+fn test_export() {
+    use glam::Vec3;
+
+    use crate::builtins::primitives::Cuboid;
+
+    let mut world = World::new();
+    for (x, visibility) in [
+        (0.0, None),
+        (2.0, Some(Visibility::Visible)),
+        (4.0, Some(Visibility::Culled)),
+    ] {
+        let entity = world.new_entity();
+        world.add_component_to_entity(entity, Mesh::from(Cuboid::default()));
+        world.add_component_to_entity(entity, Transform::from_xyz(x, 0.0, 0.0));
+        if let Some(visibility) = visibility {
+            world.add_component_to_entity(entity, visibility);
+        }
+    }
+    let cube = Mesh::from(Cuboid::default());
+    // Entities without a visibility count as visible
+    let visible = bake_world(&world, true);
+    assert_eq!(visible.vertex_count(), 2 * cube.vertex_count());
+    let bounds = visible.compute_aabb().unwrap();
+    assert!(bounds.max.abs_diff_eq(Vec3::new(2.5, 0.5, 0.5), 1e-5));
+
+    let baked = bake_world(&world, false);
+    assert_eq!(baked.vertex_count(), 3 * cube.vertex_count());
+    assert_eq!(baked.indices.len(), 3 * cube.indices.len());
+    let bounds = baked.compute_aabb().unwrap();
+    assert!(bounds.max.abs_diff_eq(Vec3::new(4.5, 0.5, 0.5), 1e-5));
+
+    let triangles = baked.indices.len() / 3;
+    let mut stl = Vec::new();
+    write_stl(&baked, &mut stl).unwrap();
+    assert_eq!(stl.len(), 84 + 50 * triangles);
+    assert_eq!(stl[80..84], (triangles as u32).to_le_bytes());
+
+    let mut ply = Vec::new();
+    write_ply(&baked, &mut ply, PlyEncoding::BinaryLittleEndian).unwrap();
+    let header = b"end_header\n";
+    let body = ply.windows(header.len()).position(|w| w == header).unwrap() + header.len();
+    assert_eq!(ply.len() - body, baked.vertex_count() * 32 + triangles * 13);
+
+    let mut ply = Vec::new();
+    write_ply(&baked, &mut ply, PlyEncoding::Ascii).unwrap();
+    let ply = String::from_utf8(ply).unwrap();
+    let body = ply.split("end_header\n").nth(1).unwrap();
+    assert_eq!(body.lines().count(), baked.vertex_count() + triangles);
+    assert_eq!(body.lines().last().unwrap().split(' ').count(), 4);
+
+    // Degenerate triangles can be exported, an empty world can't
+    let path = std::env::temp_dir().join(format!("carnot-export-{}.stl", std::process::id()));
+    let mut degenerate = cube.clone();
+    degenerate.indices.extend([0, 0, 0]);
+    save_mesh(&degenerate, &path, MeshFormat::Stl).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        export_world(&World::new(), &path, MeshFormat::Stl, false),
+        Err(ExportError::Empty)
+    ));
+    assert!(!path.exists());
+}
@@ -0,0 +1,660 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use glam::{Mat4, Quat, Vec2, Vec3};
+use thiserror::Error;
+
+use crate::builtins::systems::hierarchy::{LocalTransform, Parent};
+use crate::ecs::World;
+use crate::graphics::camera::ScalingMode;
+use crate::graphics::material::LambertMaterial;
+use crate::graphics::mesh::{Mesh, MeshAttribute, MeshError};
+use crate::graphics::{OrthographicCamera, PerspectiveCamera, Projection, Transform};
+
+/// Extensions that can be listed in `extensionsRequired`, because ignoring them still gives a
+/// reasonable result.
+const SUPPORTED_EXTENSIONS: &[&str] = &["KHR_materials_unlit"];
+
+/// The far plane of perspective cameras without one, which glTF allows but
+/// [`DepthMode::Standard`](crate::render::DepthMode::Standard) does not.
+const DEFAULT_FAR: f32 = 1000.0;
+
+#[derive(Debug, Error)]
+pub enum GltfError {
+    #[error(transparent)]
+    Gltf(#[from] gltf::Error),
+    #[error("Unsupported extension: {0}")]
+    UnsupportedExtension(String),
+    #[error("Primitive {primitive} of mesh {mesh} has no positions")]
+    MissingPositions { mesh: usize, primitive: usize },
+    #[error("Primitive {primitive} of mesh {mesh} has unsupported mode {mode:?}")]
+    UnsupportedMode {
+        mesh: usize,
+        primitive: usize,
+        mode: gltf::mesh::Mode,
+    },
+    #[error("Primitive {primitive} of mesh {mesh} is invalid: {source}")]
+    InvalidMesh {
+        mesh: usize,
+        primitive: usize,
+        #[source]
+        source: MeshError,
+    },
+    #[error("Image {image} has unsupported format {format:?}")]
+    UnsupportedImageFormat {
+        image: usize,
+        format: gltf::image::Format,
+    },
+}
+
+/// A node of a [`GltfScene`].
+#[derive(Debug, Clone)]
+pub struct GltfNode {
+    pub name: Option<String>,
+    /// The index of the parent node, which always comes before its children.
+    pub parent: Option<usize>,
+    /// The transform relative to the parent.
+    pub transform: Transform,
+    /// The index into [`GltfScene::meshes`].
+    pub mesh: Option<usize>,
+    pub camera: Option<Projection>,
+}
+
+#[derive(Clone)]
+pub struct GltfMesh {
+    pub name: Option<String>,
+    pub primitives: Vec<GltfPrimitive>,
+}
+
+#[derive(Clone)]
+pub struct GltfPrimitive {
+    pub mesh: Mesh,
+    /// The index into [`GltfScene::materials`], or `None` for the default material.
+    pub material: Option<usize>,
+}
+
+/// The parts of a glTF metallic-roughness material that are imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMaterial {
+    pub name: Option<String>,
+    /// Linear RGBA.
+    pub base_color: [f32; 4],
+    /// The index into [`GltfScene::images`].
+    pub base_color_texture: Option<usize>,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: [f32; 3],
+}
+
+impl Default for GltfMaterial {
+    /// The default material of the glTF specification.
+    fn default() -> Self {
+        Self {
+            name: None,
+            base_color: [1.0; 4],
+            base_color_texture: None,
+            metallic: 1.0,
+            roughness: 1.0,
+            emissive: [0.0; 3],
+        }
+    }
+}
+
+impl From<&GltfMaterial> for LambertMaterial {
+    /// Uses the base color as the diffuse color, and a fifth of it plus the emissive color as the
+    /// ambient color. Textures are not supported by the built-in shader.
+    fn from(material: &GltfMaterial) -> Self {
+        let [r, g, b, a] = material.base_color;
+        let base_color = Vec3::new(r, g, b);
+        Self {
+            ambient: (base_color * 0.2 + Vec3::from(material.emissive)).to_array(),
+            diffuse: base_color.to_array(),
+            opacity: a,
+            padding: 0.0,
+        }
+    }
+}
+
+/// An image converted to 8-bit RGBA.
+#[derive(Debug, Clone)]
+pub struct GltfImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Component with the base color texture of an entity spawned by [`GltfScene::spawn`], shared
+/// between all instances. The built-in shader does not sample it.
+#[derive(Debug, Clone)]
+pub struct BaseColorTexture(pub Arc<GltfImage>);
+
+/// Component on every entity spawned by [`GltfScene::spawn`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GltfNodeInstance {
+    /// The index into [`GltfScene::nodes`].
+    pub node: usize,
+}
+
+/// A glTF scene that can be [spawned](GltfScene::spawn) into a world any number of times.
+#[derive(Clone, Default)]
+pub struct GltfScene {
+    /// The nodes of the default scene (or the first one), parents before their children.
+    pub nodes: Vec<GltfNode>,
+    pub meshes: Vec<GltfMesh>,
+    pub materials: Vec<GltfMaterial>,
+    pub images: Vec<Arc<GltfImage>>,
+    /// Extensions the file uses that were ignored, because they are optional.
+    pub ignored_extensions: Vec<String>,
+}
+
+impl GltfScene {
+    /// Loads a `.gltf` or `.glb` file and the buffers and images it references.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GltfError> {
+        let path = path.as_ref();
+        let slice = std::fs::read(path).map_err(gltf::Error::Io)?;
+        Self::import(&slice, Some(path.parent().unwrap_or(Path::new("./"))))
+    }
+
+    /// Loads a `.gltf` or `.glb` file from memory. Buffers and images have to be embedded.
+    pub fn from_slice(slice: &[u8]) -> Result<Self, GltfError> {
+        Self::import(slice, None)
+    }
+
+    /// Parses `slice` once, checking the required extensions before the validation that would
+    /// reject them, and loads the buffers and images relative to `base`.
+    fn import(slice: &[u8], base: Option<&Path>) -> Result<Self, GltfError> {
+        let gltf::Gltf { document, blob } = gltf::Gltf::from_slice_without_validation(slice)?;
+        check_extensions(&document)?;
+        let document = gltf::Document::from_json(document.into_json())?;
+        let buffers = gltf::import_buffers(&document, base, blob)?;
+        let images = gltf::import_images(&document, base, &buffers)?;
+        Self::from_import(&document, &buffers, images)
+    }
+
+    fn from_import(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        images: Vec<gltf::image::Data>,
+    ) -> Result<Self, GltfError> {
+        let mut scene = GltfScene {
+            ignored_extensions: document
+                .extensions_used()
+                .filter(|extension| !SUPPORTED_EXTENSIONS.contains(extension))
+                .map(str::to_string)
+                .collect(),
+            ..Default::default()
+        };
+
+        for mesh in document.meshes() {
+            let primitives = mesh
+                .primitives()
+                .map(|primitive| load_primitive(&mesh, &primitive, buffers))
+                .collect::<Result<_, _>>()?;
+            scene.meshes.push(GltfMesh {
+                name: mesh.name().map(str::to_string),
+                primitives,
+            });
+        }
+
+        scene.materials = document
+            .materials()
+            .map(|material| {
+                let pbr = material.pbr_metallic_roughness();
+                GltfMaterial {
+                    name: material.name().map(str::to_string),
+                    base_color: pbr.base_color_factor(),
+                    base_color_texture: pbr
+                        .base_color_texture()
+                        .map(|info| info.texture().source().index()),
+                    metallic: pbr.metallic_factor(),
+                    roughness: pbr.roughness_factor(),
+                    emissive: material.emissive_factor(),
+                }
+            })
+            .collect();
+
+        scene.images = images
+            .into_iter()
+            .enumerate()
+            .map(|(index, image)| convert_image(index, image).map(Arc::new))
+            .collect::<Result<_, _>>()?;
+
+        let roots = document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+            .into_iter()
+            .flat_map(|gltf_scene| gltf_scene.nodes());
+        for root in roots {
+            scene.add_node(&root, None);
+        }
+        Ok(scene)
+    }
+
+    fn add_node(&mut self, node: &gltf::Node, parent: Option<usize>) {
+        let (translation, rotation, scale) = node.transform().decomposed();
+        let index = self.nodes.len();
+        self.nodes.push(GltfNode {
+            name: node.name().map(str::to_string),
+            parent,
+            transform: Transform {
+                translation: Vec3::from(translation),
+                rotation: Quat::from_array(rotation),
+                scale: Vec3::from(scale),
+            },
+            mesh: node.mesh().map(|mesh| mesh.index()),
+            camera: node.camera().map(|camera| convert_camera(&camera)),
+        });
+        for child in node.children() {
+            self.add_node(&child, Some(index));
+        }
+    }
+
+    /// Adds an entity for every node with a [`GltfNodeInstance`], returning the entities in the
+    /// order of [`GltfScene::nodes`].
+    ///
+    /// Root nodes get their [`Transform`] under `root`. The other nodes get the entity of their
+    /// parent node as [`Parent`] and their transform as [`LocalTransform`], so they follow the
+    /// root nodes when [`propagate_transforms_system`] runs. Their `Transform` is already set to
+    /// the world-space one.
+    ///
+    /// A node with a mesh gets its [`Mesh`], [`LambertMaterial`] and [`BaseColorTexture`] if the
+    /// mesh has a single primitive, or a child entity for each primitive otherwise. A node with a
+    /// camera gets its [`Projection`], but no [`Camera`](crate::graphics::Camera), so it only
+    /// renders once one is added.
+    ///
+    /// [`propagate_transforms_system`]: crate::builtins::systems::hierarchy::propagate_transforms_system
+    pub fn spawn(&self, world: &mut World, root: Transform) -> Vec<usize> {
+        let mut entities = Vec::with_capacity(self.nodes.len());
+        let mut world_matrices = Vec::<Mat4>::with_capacity(self.nodes.len());
+        for (index, node) in self.nodes.iter().enumerate() {
+            let parent_matrix = node
+                .parent
+                .map_or(root.compute_matrix(), |parent| world_matrices[parent]);
+            let world_matrix = parent_matrix * node.transform.compute_matrix();
+            world_matrices.push(world_matrix);
+            let transform = Transform::from(world_matrix);
+
+            let entity = world.new_entity();
+            world.add_component_to_entity(entity, transform);
+            world.add_component_to_entity(entity, GltfNodeInstance { node: index });
+            if let Some(parent) = node.parent {
+                world.add_component_to_entity(entity, Parent(entities[parent]));
+                world.add_component_to_entity(entity, LocalTransform(node.transform));
+            }
+            if let Some(camera) = &node.camera {
+                world.add_component_to_entity(entity, camera.clone());
+            }
+            if let Some(mesh) = node.mesh.map(|mesh| &self.meshes[mesh]) {
+                if let [primitive] = mesh.primitives.as_slice() {
+                    self.add_primitive(world, entity, primitive);
+                } else {
+                    for primitive in &mesh.primitives {
+                        let child = world.new_entity();
+                        world.add_component_to_entity(child, transform);
+                        world.add_component_to_entity(child, GltfNodeInstance { node: index });
+                        world.add_component_to_entity(child, Parent(entity));
+                        world.add_component_to_entity(child, LocalTransform(Transform::IDENTITY));
+                        self.add_primitive(world, child, primitive);
+                    }
+                }
+            }
+            entities.push(entity);
+        }
+        entities
+    }
+
+    fn add_primitive(&self, world: &mut World, entity: usize, primitive: &GltfPrimitive) {
+        let default_material = GltfMaterial::default();
+        let material = primitive
+            .material
+            .map_or(&default_material, |material| &self.materials[material]);
+        world.add_component_to_entity(entity, primitive.mesh.clone());
+        world.add_component_to_entity(entity, LambertMaterial::from(material));
+        if let Some(image) = material.base_color_texture {
+            world.add_component_to_entity(entity, BaseColorTexture(self.images[image].clone()));
+        }
+    }
+}
+
+fn check_extensions(document: &gltf::Document) -> Result<(), GltfError> {
+    match document
+        .extensions_required()
+        .find(|extension| !SUPPORTED_EXTENSIONS.contains(extension))
+    {
+        Some(extension) => Err(GltfError::UnsupportedExtension(extension.to_string())),
+        None => Ok(()),
+    }
+}
+
+fn load_primitive(
+    mesh: &gltf::Mesh,
+    primitive: &gltf::Primitive,
+    buffers: &[gltf::buffer::Data],
+) -> Result<GltfPrimitive, GltfError> {
+    if primitive.mode() != gltf::mesh::Mode::Triangles {
+        return Err(GltfError::UnsupportedMode {
+            mesh: mesh.index(),
+            primitive: primitive.index(),
+            mode: primitive.mode(),
+        });
+    }
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+    let positions = reader
+        .read_positions()
+        .ok_or(GltfError::MissingPositions {
+            mesh: mesh.index(),
+            primitive: primitive.index(),
+        })?
+        .collect::<Vec<_>>();
+    let indices = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..positions.len() as u32).collect::<Vec<_>>(),
+    };
+    // The built-in pipeline needs texture coordinates even for untextured materials
+    let tex_coords = match reader.read_tex_coords(0) {
+        Some(tex_coords) => tex_coords.into_f32().collect(),
+        None => vec![[0.0; 2]; positions.len()],
+    };
+
+    let mesh_index = mesh.index();
+    let mut mesh = Mesh::default()
+        .with_attribute(MeshAttribute::POSITION, positions)
+        .with_attribute(MeshAttribute::TEX_COORDS, tex_coords)
+        .with_indices(indices);
+    let has_normals = match reader.read_normals() {
+        Some(normals) => {
+            mesh.insert_attribute(MeshAttribute::NORMAL, normals.collect::<Vec<_>>());
+            true
+        }
+        None => false,
+    };
+    if let Some(tex_coords) = reader.read_tex_coords(1) {
+        let tex_coords = tex_coords.into_f32().collect::<Vec<_>>();
+        mesh.insert_attribute(MeshAttribute::TEX_COORDS_1, tex_coords);
+    }
+    if let Some(tangents) = reader.read_tangents() {
+        mesh.insert_attribute(MeshAttribute::TANGENT, tangents.collect::<Vec<_>>());
+    }
+    if let Some(colors) = reader.read_colors(0) {
+        let colors = colors.into_rgba_f32().collect::<Vec<_>>();
+        mesh.insert_attribute(MeshAttribute::COLOR, colors);
+    }
+    if let Some(joints) = reader.read_joints(0) {
+        let joints = joints.into_u16().collect::<Vec<_>>();
+        mesh.insert_attribute(MeshAttribute::JOINT_INDICES, joints);
+    }
+    if let Some(weights) = reader.read_weights(0) {
+        let weights = weights.into_f32().collect::<Vec<_>>();
+        mesh.insert_attribute(MeshAttribute::JOINT_WEIGHTS, weights);
+    }
+    // Index values are not range-checked by the gltf crate
+    mesh.validate_drawable()
+        .map_err(|source| GltfError::InvalidMesh {
+            mesh: mesh_index,
+            primitive: primitive.index(),
+            source,
+        })?;
+    if !has_normals {
+        mesh.compute_smooth_normals();
+    }
+    Ok(GltfPrimitive {
+        mesh,
+        material: primitive.material().index(),
+    })
+}
+
+fn convert_camera(camera: &gltf::Camera) -> Projection {
+    match camera.projection() {
+        gltf::camera::Projection::Perspective(perspective) => {
+            let aspect_ratio = perspective.aspect_ratio().unwrap_or(1.0);
+            let fov_x = 2.0 * ((perspective.yfov() / 2.0).tan() * aspect_ratio).atan();
+            PerspectiveCamera::new(
+                fov_x.to_degrees(),
+                aspect_ratio,
+                perspective.znear(),
+                perspective.zfar().unwrap_or(DEFAULT_FAR),
+            )
+            .into()
+        }
+        gltf::camera::Projection::Orthographic(orthographic) => OrthographicCamera::new(
+            ScalingMode::FixedVertical {
+                height: 2.0 * orthographic.ymag(),
+            },
+            Vec2::new(orthographic.xmag(), orthographic.ymag()),
+            orthographic.znear(),
+            orthographic.zfar(),
+        )
+        .into(),
+    }
+}
+
+fn convert_image(index: usize, image: gltf::image::Data) -> Result<GltfImage, GltfError> {
+    use gltf::image::Format;
+
+    // 16-bit channels keep their most significant byte
+    let (channels, bytes_per_channel) = match image.format {
+        Format::R8 => (1, 1),
+        Format::R8G8 => (2, 1),
+        Format::R8G8B8 => (3, 1),
+        Format::R8G8B8A8 => (4, 1),
+        Format::R16 => (1, 2),
+        Format::R16G16 => (2, 2),
+        Format::R16G16B16 => (3, 2),
+        Format::R16G16B16A16 => (4, 2),
+        format => {
+            return Err(GltfError::UnsupportedImageFormat {
+                image: index,
+                format,
+            })
+        }
+    };
+    let pixels = image
+        .pixels
+        .chunks_exact(channels * bytes_per_channel)
+        .flat_map(|pixel| {
+            let channel = |i: usize| match bytes_per_channel {
+                1 => pixel[i],
+                _ => (u16::from_ne_bytes([pixel[2 * i], pixel[2 * i + 1]]) >> 8) as u8,
+            };
+            match channels {
+                1 => [channel(0), channel(0), channel(0), 255],
+                2 => [channel(0), channel(0), channel(0), channel(1)],
+                3 => [channel(0), channel(1), channel(2), 255],
+                _ => [channel(0), channel(1), channel(2), channel(3)],
+            }
+        })
+        .collect();
+    Ok(GltfImage {
+        width: image.width,
+        height: image.height,
+        pixels,
+    })
+}
+
+#[test]
+fn test_gltf_scene() {
+    let gltf = r#"{
+        "asset": { "version": "2.0" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [
+            { "name": "root", "translation": [0, 1, 0], "children": [1, 2] },
+            { "name": "triangle", "scale": [2, 2, 2], "mesh": 0 },
+            { "name": "camera", "camera": 0 }
+        ],
+        "cameras": [{
+            "type": "perspective",
+            "perspective": { "yfov": 1.0, "aspectRatio": 1.5, "znear": 0.1 }
+        }],
+        "meshes": [{ "primitives": [
+            { "attributes": { "POSITION": 0 }, "material": 0 },
+            { "attributes": { "POSITION": 0 } }
+        ] }],
+        "materials": [{ "pbrMetallicRoughness": { "baseColorFactor": [1, 0, 0, 0.5] } }],
+        "accessors": [{
+            "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+            "min": [0, 0, 0], "max": [1, 1, 0]
+        }],
+        "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+        "buffers": [{
+            "byteLength": 36,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"
+        }]
+    }"#;
+    let scene = GltfScene::from_slice(gltf.as_bytes()).unwrap();
+    assert_eq!(scene.nodes.len(), 3);
+    assert_eq!(scene.nodes[2].parent, Some(0));
+    let primitive = &scene.meshes[0].primitives[0];
+    assert_eq!(primitive.mesh.indices, [0, 1, 2]);
+    assert_eq!(primitive.mesh.normals().unwrap()[0], [0.0, 0.0, 1.0]);
+    assert_eq!(LambertMaterial::from(&scene.materials[0]).opacity, 0.5);
+    let Some(Projection::Perspective(camera)) = &scene.nodes[2].camera else {
+        panic!("expected a perspective camera");
+    };
+    assert_eq!(camera.get_far(), DEFAULT_FAR);
+
+    // Spawning twice gives independent copies
+    let mut world = World::new();
+    for x in [0.0, 10.0] {
+        let entities = scene.spawn(&mut world, Transform::from_xyz(x, 0.0, 0.0));
+        assert_eq!(entities.len(), 3);
+    }
+    // The root, the triangle node with one entity per primitive and the camera, twice
+    assert_eq!(world.num_entities, 10);
+    let transforms = world.borrow_component_vec::<Transform>().unwrap();
+    let child_transform = transforms[7].unwrap();
+    assert!(child_transform
+        .translation
+        .abs_diff_eq(Vec3::new(10.0, 1.0, 0.0), 1e-5));
+    assert!(child_transform.scale.abs_diff_eq(Vec3::splat(2.0), 1e-5));
+    drop(transforms);
+    assert_eq!(
+        world.borrow_component_vec::<GltfNodeInstance>().unwrap()[7],
+        Some(GltfNodeInstance { node: 1 })
+    );
+    let parents = world.borrow_component_vec::<Parent>().unwrap();
+    assert_eq!(
+        (parents[5], parents[6], parents[7]),
+        (None, Some(Parent(5)), Some(Parent(6)))
+    );
+    drop(parents);
+
+    // Moving the root node moves the rest of the copy
+    world.borrow_component_vec_mut::<Transform>().unwrap()[5]
+        .as_mut()
+        .unwrap()
+        .translation
+        .x += 10.0;
+    crate::builtins::systems::hierarchy::propagate_transforms_system(&mut world);
+    let transforms = world.borrow_component_vec::<Transform>().unwrap();
+    assert!(transforms[7]
+        .unwrap()
+        .translation
+        .abs_diff_eq(Vec3::new(20.0, 1.0, 0.0), 1e-5));
+    assert!(transforms[2]
+        .unwrap()
+        .translation
+        .abs_diff_eq(Vec3::new(0.0, 1.0, 0.0), 1e-5));
+    drop(transforms);
+
+    // Files reference their buffers relative to their own directory
+    let directory = std::env::temp_dir().join(format!("carnot-gltf-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let data_uri = "data:application/octet-stream;base64,";
+    let data_start = gltf.find(data_uri).unwrap() + data_uri.len();
+    let data_end = data_start + gltf[data_start..].find('"').unwrap();
+    let buffer = [[0.0f32; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+    std::fs::write(
+        directory.join("triangle.bin"),
+        bytemuck::cast_slice(&buffer),
+    )
+    .unwrap();
+    let external = gltf.replace(&gltf[data_start - data_uri.len()..data_end], "triangle.bin");
+    std::fs::write(directory.join("scene.gltf"), external).unwrap();
+    let loaded = GltfScene::load(directory.join("scene.gltf"));
+    std::fs::remove_dir_all(&directory).unwrap();
+    assert_eq!(loaded.unwrap().nodes.len(), 3);
+
+    let unsupported = gltf.replace(
+        r#""scene": 0,"#,
+        r#""scene": 0, "extensionsUsed": ["EXT_meshopt_compression"], "extensionsRequired": ["EXT_meshopt_compression"],"#,
+    );
+    assert!(matches!(
+        GltfScene::from_slice(unsupported.as_bytes()),
+        Err(GltfError::UnsupportedExtension(extension)) if extension == "EXT_meshopt_compression"
+    ));
+}
+
+#[test]
+fn test_gltf_invalid_indices() {
+    // The gltf crate doesn't range-check index values, here 5 for 3 vertices
+    let gltf = r#"{
+        "asset": { "version": "2.0" },
+        "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1 }] }],
+        "accessors": [
+            {
+                "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                "min": [0, 0, 0], "max": [1, 1, 0]
+            },
+            { "bufferView": 1, "componentType": 5125, "count": 3, "type": "SCALAR" }
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 36, "byteLength": 12 }
+        ],
+        "buffers": [{
+            "byteLength": 48,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAEAAAAFAAAA"
+        }]
+    }"#;
+    assert!(matches!(
+        GltfScene::from_slice(gltf.as_bytes()),
+        Err(GltfError::InvalidMesh {
+            mesh: 0,
+            primitive: 0,
+            source: MeshError::IndexOutOfRange {
+                index: 5,
+                vertices: 3
+            }
+        })
+    ));
+}
+
+#[test]
+fn test_gltf_texture() {
+    use crate::builtins::primitives::Cuboid;
+
+    let image = Arc::new(GltfImage {
+        width: 1,
+        height: 1,
+        pixels: vec![255, 0, 0, 255],
+    });
+    let scene = GltfScene {
+        nodes: vec![GltfNode {
+            name: None,
+            parent: None,
+            transform: Transform::IDENTITY,
+            mesh: Some(0),
+            camera: None,
+        }],
+        meshes: vec![GltfMesh {
+            name: None,
+            primitives: vec![GltfPrimitive {
+                mesh: Cuboid::default().into(),
+                material: Some(0),
+            }],
+        }],
+        materials: vec![GltfMaterial {
+            base_color_texture: Some(0),
+            ..Default::default()
+        }],
+        images: vec![image.clone()],
+        ..Default::default()
+    };
+    let mut world = World::new();
+    let [entity] = scene.spawn(&mut world, Transform::IDENTITY)[..] else {
+        panic!("expected one entity");
+    };
+    let textures = world.borrow_component_vec::<BaseColorTexture>().unwrap();
+    assert!(Arc::ptr_eq(&textures[entity].as_ref().unwrap().0, &image));
+}
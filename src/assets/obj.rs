@@ -0,0 +1,543 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use glam::{Vec2, Vec3};
+use thiserror::Error;
+
+use crate::ecs::World;
+use crate::graphics::material::{LambertMaterial, PhongMaterial};
+use crate::graphics::mesh::{Mesh, MeshVertex};
+use crate::graphics::Transform;
+
+#[derive(Debug, Error)]
+pub enum ObjError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Line {line}: {kind}")]
+    Parse { line: usize, kind: ObjParseError },
+    #[error("In material library {path}: {source}")]
+    MaterialLibrary {
+        path: PathBuf,
+        #[source]
+        source: Box<ObjError>,
+    },
+}
+
+/// What is wrong with a line of an OBJ or MTL file.
+#[derive(Debug, Error, PartialEq)]
+pub enum ObjParseError {
+    #[error("Invalid number `{0}`")]
+    InvalidNumber(String),
+    #[error("Expected {expected} values but found {found}")]
+    WrongValueCount { expected: usize, found: usize },
+    #[error("Invalid face vertex `{0}`")]
+    InvalidFaceVertex(String),
+    #[error("Index {0} is out of range")]
+    IndexOutOfRange(i64),
+    #[error("Face has fewer than 3 vertices")]
+    DegenerateFace,
+    #[error("Missing name")]
+    MissingName,
+    #[error("Material property before `newmtl`")]
+    NoMaterial,
+}
+
+/// A material from an MTL file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial {
+    /// `Ka`
+    pub ambient: [f32; 3],
+    /// `Kd`
+    pub diffuse: [f32; 3],
+    /// `Ks`
+    pub specular: [f32; 3],
+    /// `Ns`
+    pub shininess: f32,
+    /// `d`, or one minus `Tr`
+    pub opacity: f32,
+}
+
+impl Default for ObjMaterial {
+    fn default() -> Self {
+        Self {
+            ambient: [0.2; 3],
+            diffuse: [0.8; 3],
+            specular: [0.0; 3],
+            shininess: 0.0,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl From<&ObjMaterial> for LambertMaterial {
+    fn from(material: &ObjMaterial) -> Self {
+        Self {
+            ambient: material.ambient,
+            diffuse: material.diffuse,
+            opacity: material.opacity,
+            padding: 0.0,
+        }
+    }
+}
+
+impl From<&ObjMaterial> for PhongMaterial {
+    fn from(material: &ObjMaterial) -> Self {
+        Self {
+            ambient: material.ambient,
+            diffuse: material.diffuse,
+            specular: material.specular,
+            shininess: material.shininess,
+            opacity: material.opacity,
+        }
+    }
+}
+
+/// The faces of an object or group that use the same material.
+pub struct ObjMesh {
+    /// The name of the object (`o`) or group (`g`), or empty for faces before the first one.
+    pub name: String,
+    /// The name of the material selected with `usemtl`.
+    pub material: Option<String>,
+    pub mesh: Mesh,
+}
+
+/// The meshes and materials of an OBJ file.
+#[derive(Default)]
+pub struct ObjScene {
+    pub meshes: Vec<ObjMesh>,
+    /// The files named by `mtllib`, relative to the OBJ file.
+    pub material_libraries: Vec<String>,
+    pub materials: HashMap<String, ObjMaterial>,
+}
+
+impl ObjScene {
+    /// Parses the contents of an OBJ file without loading its material libraries.
+    pub fn parse(source: &str) -> Result<Self, ObjError> {
+        ObjParser::default().parse(source)
+    }
+
+    /// Parses the contents of an MTL file and adds its materials to the scene.
+    pub fn parse_materials(&mut self, source: &str) -> Result<(), ObjError> {
+        let mut current = None;
+        for (line, tokens) in lines(source) {
+            let parse_error = |kind| ObjError::Parse { line, kind };
+            let (keyword, args) = tokens.split_first().unwrap();
+            if *keyword == "newmtl" {
+                let name = args.join(" ");
+                if name.is_empty() {
+                    return Err(parse_error(ObjParseError::MissingName));
+                }
+                self.materials.insert(name.clone(), ObjMaterial::default());
+                current = Some(name);
+                continue;
+            }
+            if !["Ka", "Kd", "Ks", "Ns", "d", "Tr"].contains(keyword) {
+                continue;
+            }
+            let material = current
+                .as_ref()
+                .and_then(|name| self.materials.get_mut(name))
+                .ok_or_else(|| parse_error(ObjParseError::NoMaterial))?;
+            match *keyword {
+                "Ka" => material.ambient = parse_color(args).map_err(parse_error)?,
+                "Kd" => material.diffuse = parse_color(args).map_err(parse_error)?,
+                "Ks" => material.specular = parse_color(args).map_err(parse_error)?,
+                "Ns" => material.shininess = parse_floats::<1>(args).map_err(parse_error)?[0],
+                "d" => material.opacity = parse_floats::<1>(args).map_err(parse_error)?[0],
+                _ => material.opacity = 1.0 - parse_floats::<1>(args).map_err(parse_error)?[0],
+            }
+        }
+        Ok(())
+    }
+
+    /// The material of a mesh, if it has one that was loaded.
+    pub fn material(&self, mesh: &ObjMesh) -> Option<&ObjMaterial> {
+        self.materials.get(mesh.material.as_ref()?)
+    }
+
+    /// Adds every mesh to the world as an entity with the given [`Transform`] and a
+    /// [`LambertMaterial`], returning the entities.
+    pub fn spawn(self, world: &mut World, transform: Transform) -> Vec<usize> {
+        let ObjScene {
+            meshes, materials, ..
+        } = self;
+        meshes
+            .into_iter()
+            .map(|obj_mesh| {
+                let material = obj_mesh
+                    .material
+                    .as_ref()
+                    .and_then(|name| materials.get(name))
+                    .map(LambertMaterial::from)
+                    .unwrap_or_else(|| LambertMaterial::from(&ObjMaterial::default()));
+                let entity = world.new_entity();
+                world.add_component_to_entity(entity, obj_mesh.mesh);
+                world.add_component_to_entity(entity, transform);
+                world.add_component_to_entity(entity, material);
+                entity
+            })
+            .collect()
+    }
+}
+
+/// Loads an OBJ file and the MTL files it references.
+///
+/// Positions, texture coordinates and normals are indexed separately in OBJ, so vertices are
+/// duplicated wherever a position is used with different texture coordinates or normals.
+/// Faces without normals get flat normals, and polygons are triangulated as fans.
+pub fn load_obj(path: impl AsRef<Path>) -> Result<ObjScene, ObjError> {
+    let path = path.as_ref();
+    let mut scene = ObjScene::parse(&read(path)?)?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+    for library in scene.material_libraries.clone() {
+        let library_path = directory.join(library);
+        scene
+            .parse_materials(&read(&library_path)?)
+            .map_err(|source| ObjError::MaterialLibrary {
+                path: library_path,
+                source: Box::new(source),
+            })?;
+    }
+    Ok(scene)
+}
+
+/// Writes the standard attributes of `mesh` as a single OBJ object. Every vertex gets a position,
+/// texture coordinate and normal with the same index, so [`ObjScene::parse`] reads back the same
+/// triangles.
+pub fn write_obj(mesh: &Mesh, mut writer: impl Write) -> io::Result<()> {
+    let vertices = (0..mesh.vertex_count())
+        .map(|index| mesh.vertex(index))
+        .collect::<Vec<_>>();
+    for vertex in &vertices {
+        let [x, y, z] = vertex.position;
+        writeln!(writer, "v {x} {y} {z}")?;
+    }
+    for vertex in &vertices {
+        let [u, v] = vertex.tex_coords;
+        writeln!(writer, "vt {u} {v}")?;
+    }
+    for vertex in &vertices {
+        let [x, y, z] = vertex.normal;
+        writeln!(writer, "vn {x} {y} {z}")?;
+    }
+    for triangle in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| triangle[i] + 1);
+        writeln!(writer, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}")?;
+    }
+    writer.flush()
+}
+
+fn read(path: &Path) -> Result<String, ObjError> {
+    std::fs::read_to_string(path).map_err(|source| ObjError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// The non-empty lines without comments, split into whitespace-separated tokens, with their
+/// 1-based line numbers.
+fn lines(source: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    source.lines().enumerate().filter_map(|(index, line)| {
+        let line = line.split('#').next().unwrap();
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        (!tokens.is_empty()).then_some((index + 1, tokens))
+    })
+}
+
+fn parse_float(token: &str) -> Result<f32, ObjParseError> {
+    token
+        .parse()
+        .map_err(|_| ObjParseError::InvalidNumber(token.to_string()))
+}
+
+/// Parses exactly `N` numbers.
+fn parse_floats<const N: usize>(args: &[&str]) -> Result<[f32; N], ObjParseError> {
+    if args.len() != N {
+        return Err(ObjParseError::WrongValueCount {
+            expected: N,
+            found: args.len(),
+        });
+    }
+    let mut values = [0.0; N];
+    for (value, arg) in values.iter_mut().zip(args) {
+        *value = parse_float(arg)?;
+    }
+    Ok(values)
+}
+
+/// Parses `r [g b]`, where a single value is used for all channels.
+fn parse_color(args: &[&str]) -> Result<[f32; 3], ObjParseError> {
+    match args {
+        [value] => Ok([parse_float(value)?; 3]),
+        _ => parse_floats::<3>(args),
+    }
+}
+
+/// Where a vertex's normal comes from, which decides whether vertices can be shared.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum NormalKey {
+    Index(usize),
+    /// The bits of a generated flat normal.
+    Flat([u32; 3]),
+}
+
+/// A vertex as the `(position, texture coordinates, normal)` it is made of.
+type VertexKey = (usize, Option<usize>, NormalKey);
+
+#[derive(Default)]
+struct ObjParser {
+    positions: Vec<Vec3>,
+    tex_coords: Vec<Vec2>,
+    normals: Vec<Vec3>,
+    scene: ObjScene,
+    name: String,
+    material: Option<String>,
+    mesh: Mesh,
+    vertex_indices: HashMap<VertexKey, u32>,
+}
+
+impl ObjParser {
+    fn parse(mut self, source: &str) -> Result<ObjScene, ObjError> {
+        for (line, tokens) in lines(source) {
+            self.parse_line(&tokens)
+                .map_err(|kind| ObjError::Parse { line, kind })?;
+        }
+        self.finish_mesh();
+        Ok(self.scene)
+    }
+
+    fn parse_line(&mut self, tokens: &[&str]) -> Result<(), ObjParseError> {
+        let (keyword, args) = tokens.split_first().unwrap();
+        match *keyword {
+            "v" => {
+                // An optional w or vertex color may follow
+                let [x, y, z] = parse_floats(args.get(..3).unwrap_or(args))?;
+                self.positions.push(Vec3::new(x, y, z));
+            }
+            "vt" => {
+                let args = args.get(..2).unwrap_or(args);
+                let [u, v] = match args {
+                    [u] => [parse_float(u)?, 0.0],
+                    _ => parse_floats(args)?,
+                };
+                self.tex_coords.push(Vec2::new(u, v));
+            }
+            "vn" => {
+                let [x, y, z] = parse_floats(args)?;
+                self.normals.push(Vec3::new(x, y, z));
+            }
+            "f" => self.parse_face(args)?,
+            "o" | "g" => {
+                self.finish_mesh();
+                self.name = args.join(" ");
+            }
+            "usemtl" => {
+                self.finish_mesh();
+                self.material = Some(args.join(" ")).filter(|name| !name.is_empty());
+            }
+            "mtllib" => {
+                if args.is_empty() {
+                    return Err(ObjParseError::MissingName);
+                }
+                self.scene
+                    .material_libraries
+                    .extend(args.iter().map(|arg| arg.to_string()));
+            }
+            // Smoothing groups, lines, points and free-form geometry are not supported
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Resolves a 1-based or negative (relative to the end) OBJ index.
+    fn resolve_index(token: &str, len: usize) -> Result<usize, ObjParseError> {
+        let index = token
+            .parse::<i64>()
+            .map_err(|_| ObjParseError::InvalidFaceVertex(token.to_string()))?;
+        let resolved = if index < 0 {
+            len as i64 + index
+        } else {
+            index - 1
+        };
+        if (0..len as i64).contains(&resolved) {
+            Ok(resolved as usize)
+        } else {
+            Err(ObjParseError::IndexOutOfRange(index))
+        }
+    }
+
+    fn parse_face(&mut self, args: &[&str]) -> Result<(), ObjParseError> {
+        if args.len() < 3 {
+            return Err(ObjParseError::DegenerateFace);
+        }
+        let mut corners = Vec::with_capacity(args.len());
+        for arg in args {
+            let mut parts = arg.split('/');
+            let position = Self::resolve_index(parts.next().unwrap(), self.positions.len())?;
+            let tex_coords = match parts.next() {
+                None | Some("") => None,
+                Some(token) => Some(Self::resolve_index(token, self.tex_coords.len())?),
+            };
+            let normal = match parts.next() {
+                None | Some("") => None,
+                Some(token) => Some(Self::resolve_index(token, self.normals.len())?),
+            };
+            if parts.next().is_some() {
+                return Err(ObjParseError::InvalidFaceVertex(arg.to_string()));
+            }
+            corners.push((position, tex_coords, normal));
+        }
+
+        // Newell's method, which also works for polygons that are not quite planar
+        let flat_normal = corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .fold(Vec3::ZERO, |normal, (a, b)| {
+                let (a, b) = (self.positions[a.0], self.positions[b.0]);
+                normal
+                    + Vec3::new(
+                        (a.y - b.y) * (a.z + b.z),
+                        (a.z - b.z) * (a.x + b.x),
+                        (a.x - b.x) * (a.y + b.y),
+                    )
+            })
+            .normalize_or_zero();
+
+        let indices = corners
+            .into_iter()
+            .map(|(position, tex_coords, normal)| {
+                let normal = match normal {
+                    Some(index) => NormalKey::Index(index),
+                    None => NormalKey::Flat(flat_normal.to_array().map(f32::to_bits)),
+                };
+                self.vertex_index((position, tex_coords, normal))
+            })
+            .collect::<Vec<_>>();
+        for i in 1..indices.len() - 1 {
+            self.mesh
+                .indices
+                .extend([indices[0], indices[i], indices[i + 1]]);
+        }
+        Ok(())
+    }
+
+    fn vertex_index(&mut self, key: VertexKey) -> u32 {
+        if let Some(index) = self.vertex_indices.get(&key) {
+            return *index;
+        }
+        let (position, tex_coords, normal) = key;
+        let normal = match normal {
+            NormalKey::Index(index) => self.normals[index],
+            NormalKey::Flat(bits) => Vec3::from_array(bits.map(f32::from_bits)),
+        };
+        let index = self.mesh.push_vertex(MeshVertex {
+            position: self.positions[position].to_array(),
+            normal: normal.to_array(),
+            tex_coords: tex_coords.map_or([0.0; 2], |index| self.tex_coords[index].to_array()),
+        });
+        self.vertex_indices.insert(key, index);
+        index
+    }
+
+    /// Moves the faces parsed so far into a new [`ObjMesh`], if there are any.
+    fn finish_mesh(&mut self) {
+        self.vertex_indices.clear();
+        let mesh = std::mem::take(&mut self.mesh);
+        if !mesh.indices.is_empty() {
+            self.scene.meshes.push(ObjMesh {
+                name: self.name.clone(),
+                material: self.material.clone(),
+                mesh,
+            });
+        }
+    }
+}
+
+#[test]
+fn test_parse_obj() {
+    let mut scene = ObjScene::parse(
+        "mtllib scene.mtl
+        v 0 0 0
+        v 1 0 0
+        v 1 1 0
+        v 0 1 0
+        vt 0 0
+        vt 1 1
+        vn 0 0 1
+        o quad
+        usemtl red
+        f 1/1 2/2 3/1 4/2 # flat normals, shared between the two triangles
+        g first second
+        f -4//1 -3//1 -2//1
+        f 1/1/1 2/2/1 3/2/1",
+    )
+    .unwrap();
+    assert_eq!(scene.material_libraries, ["scene.mtl"]);
+    assert_eq!(scene.meshes.len(), 2);
+
+    let quad = &scene.meshes[0];
+    assert_eq!(
+        (quad.name.as_str(), quad.material.as_deref()),
+        ("quad", Some("red"))
+    );
+    assert_eq!(quad.mesh.vertex_count(), 4);
+    assert_eq!(quad.mesh.indices, [0, 1, 2, 0, 2, 3]);
+    assert!(quad
+        .mesh
+        .normals()
+        .unwrap()
+        .iter()
+        .all(|normal| *normal == [0.0, 0.0, 1.0]));
+
+    // The same positions with different texture coordinates are separate vertices
+    let group = &scene.meshes[1];
+    assert_eq!(group.name, "first second");
+    assert_eq!(group.mesh.vertex_count(), 6);
+    assert_eq!(group.mesh.vertex(5).tex_coords, [1.0, 1.0]);
+
+    scene
+        .parse_materials("newmtl red\nKa 0.1\nKd 1 0 0\nKs 0.5 0.5 0.5\nNs 32\nd 0.5")
+        .unwrap();
+    let material = scene.material(&scene.meshes[0]).unwrap();
+    assert_eq!(material.ambient, [0.1; 3]);
+    assert_eq!(LambertMaterial::from(material).diffuse, [1.0, 0.0, 0.0]);
+    assert_eq!(PhongMaterial::from(material).shininess, 32.0);
+    assert_eq!(material.opacity, 0.5);
+
+    let error = ObjScene::parse("v 0 0 0\nv 1 0 0\n\nf 1 2 3")
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        ObjError::Parse {
+            line: 4,
+            kind: ObjParseError::IndexOutOfRange(3)
+        }
+    ));
+    assert_eq!(error.to_string(), "Line 4: Index 3 is out of range");
+}
+
+#[test]
+fn test_obj_round_trip() {
+    use crate::builtins::primitives::Torus;
+
+    let mesh = Mesh::from(Torus::default().with_segments(8, 6));
+    let mut source = Vec::new();
+    write_obj(&mesh, &mut source).unwrap();
+    let scene = ObjScene::parse(std::str::from_utf8(&source).unwrap()).unwrap();
+    assert_eq!(scene.meshes.len(), 1);
+
+    let read = &scene.meshes[0].mesh;
+    assert_eq!(read.indices.len(), mesh.indices.len());
+    for (&written, &read_back) in mesh.indices.iter().zip(&read.indices) {
+        assert_eq!(
+            mesh.vertex(written as usize),
+            read.vertex(read_back as usize)
+        );
+    }
+}
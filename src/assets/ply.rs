@@ -0,0 +1,61 @@
+use std::io::{self, Write};
+
+use crate::graphics::mesh::Mesh;
+
+/// How the vertex and face data of a PLY file is stored after its header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlyEncoding {
+    Ascii,
+    #[default]
+    BinaryLittleEndian,
+}
+
+/// Writes `mesh` as PLY with positions (`x y z`), normals (`nx ny nz`) and texture coordinates
+/// (`s t`) per vertex and one triangle per face. Other attributes are not written.
+pub fn write_ply(mesh: &Mesh, mut writer: impl Write, encoding: PlyEncoding) -> io::Result<()> {
+    let format = match encoding {
+        PlyEncoding::Ascii => "ascii",
+        PlyEncoding::BinaryLittleEndian => "binary_little_endian",
+    };
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {format} 1.0")?;
+    writeln!(writer, "element vertex {}", mesh.vertex_count())?;
+    for property in ["x", "y", "z", "nx", "ny", "nz", "s", "t"] {
+        writeln!(writer, "property float {property}")?;
+    }
+    writeln!(writer, "element face {}", mesh.indices.len() / 3)?;
+    writeln!(writer, "property list uchar uint vertex_indices")?;
+    writeln!(writer, "end_header")?;
+
+    let vertices = (0..mesh.vertex_count()).map(|index| mesh.vertex(index));
+    match encoding {
+        PlyEncoding::Ascii => {
+            for vertex in vertices {
+                let [x, y, z] = vertex.position;
+                let [nx, ny, nz] = vertex.normal;
+                let [s, t] = vertex.tex_coords;
+                writeln!(writer, "{x} {y} {z} {nx} {ny} {nz} {s} {t}")?;
+            }
+            for triangle in mesh.indices.chunks_exact(3) {
+                writeln!(writer, "3 {} {} {}", triangle[0], triangle[1], triangle[2])?;
+            }
+        }
+        PlyEncoding::BinaryLittleEndian => {
+            for vertex in vertices {
+                for value in vertex.position.iter().chain(&vertex.normal) {
+                    writer.write_all(&value.to_le_bytes())?;
+                }
+                for value in vertex.tex_coords {
+                    writer.write_all(&value.to_le_bytes())?;
+                }
+            }
+            for triangle in mesh.indices.chunks_exact(3) {
+                writer.write_all(&[3])?;
+                for index in triangle {
+                    writer.write_all(&index.to_le_bytes())?;
+                }
+            }
+        }
+    }
+    writer.flush()
+}
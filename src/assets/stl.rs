@@ -0,0 +1,29 @@
+use std::io::{self, Write};
+
+use glam::Vec3;
+
+use crate::graphics::mesh::Mesh;
+
+/// Writes `mesh` as binary STL. STL has no units, texture coordinates or shared vertices, so
+/// only the triangles are written, each with its face normal.
+pub fn write_stl(mesh: &Mesh, mut writer: impl Write) -> io::Result<()> {
+    let mut header = [0; 80];
+    let name = b"carnot";
+    header[..name.len()].copy_from_slice(name);
+    writer.write_all(&header)?;
+    writer.write_all(&(mesh.indices.len() as u32 / 3).to_le_bytes())?;
+
+    let positions = mesh.positions();
+    for triangle in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i] as usize]));
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        for vector in [normal, a, b, c] {
+            for value in vector.to_array() {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+        // The attribute byte count, which is unused.
+        writer.write_all(&[0; 2])?;
+    }
+    writer.flush()
+}
@@ -3,6 +3,10 @@ use crate::graphics::{
     mesh::{Mesh, MeshVertex},
 };
 
+pub use shapes::{Capsule, Cone, Cuboid, Cylinder, Icosphere, Plane, Torus, UvSphere};
+
+mod shapes;
+
 pub enum Primitive {
     TRIANGLE,
     PLANE,
@@ -31,21 +35,18 @@ impl ImplicitFromPrimitive for Primitive {
 impl Primitive {
     pub fn spawn(primitive: Primitive) -> Mesh {
         match primitive {
-            Primitive::TRIANGLE => Mesh {
-                vertices: Self::TRIANGLE_VERTICES.to_vec(),
-                indices: Self::TRIANGLE_INDICES.to_vec(),
-            },
-            Primitive::PLANE => Mesh {
-                vertices: Self::PLANE_VERTICES.to_vec(),
-                indices: Self::PLANE_INDICES.to_vec(),
-            },
-            Primitive::CUBE => Mesh {
-                vertices: Self::CUBE_VERTICES.to_vec(),
-                indices: Self::CUBE_INDICES.to_vec(),
-            },
-            Primitive::SPHERE => tessellate_sphere(0.5, 64, 64),
-            Primitive::CYLINDER => todo!(),
-            Primitive::CONE => todo!(),
+            Primitive::TRIANGLE => {
+                Mesh::from_vertices(&Self::TRIANGLE_VERTICES, Self::TRIANGLE_INDICES.to_vec())
+            }
+            Primitive::PLANE => {
+                Mesh::from_vertices(&Self::PLANE_VERTICES, Self::PLANE_INDICES.to_vec())
+            }
+            Primitive::CUBE => {
+                Mesh::from_vertices(&Self::CUBE_VERTICES, Self::CUBE_INDICES.to_vec())
+            }
+            Primitive::SPHERE => UvSphere::default().mesh(),
+            Primitive::CYLINDER => Cylinder::default().mesh(),
+            Primitive::CONE => Cone::default().mesh(),
         }
     }
 
@@ -233,60 +234,3 @@ impl Primitive {
         20, 21, 22, 20, 22, 23, // bottom
     ];
 }
-
-/// Tessellates a sphere with the given radius, number of rings, and number of sectors.
-/// The sphere is centered at the origin.
-/// When `rings` is 1 and `sectors` is 1, the sphere will be a single tetrahedron.
-fn tessellate_sphere(radius: f32, rings: usize, sectors: usize) -> Mesh {
-    // panic if the number of rings or sectors is less than 1
-    assert!(rings > 0);
-    assert!(sectors > 0);
-
-    let rings = rings + 2;
-    let sectors = sectors + 2;
-
-    let mut vertices = Vec::new();
-    let mut indices = Vec::new();
-
-    let pi = std::f32::consts::PI;
-    let two_pi = 2.0 * pi;
-
-    for ring in 0..=rings {
-        let phi = pi * ring as f32 / rings as f32; // 0 to PI
-        let y = phi.cos();
-        let r = phi.sin();
-
-        for sector in 0..=sectors {
-            let theta = two_pi * sector as f32 / sectors as f32; // 0 to 2PI
-            let x = r * theta.sin();
-            let z = r * theta.cos();
-
-            let s = sector as f32 / sectors as f32;
-            let t = ring as f32 / rings as f32;
-
-            vertices.push(MeshVertex {
-                position: [radius * x, radius * y, radius * z],
-                normal: [x, y, z],
-                tex_coords: [s, t],
-            });
-        }
-    }
-
-    for ring in 0..rings {
-        for sector in 0..sectors {
-            let lower = ring * (sectors + 1) + sector;
-            let upper = lower + sectors + 1;
-
-            // Each quad on the sphere is made up of two triangles
-            indices.push(lower as u32);
-            indices.push(upper as u32);
-            indices.push((lower + 1) as u32);
-
-            indices.push(upper as u32);
-            indices.push((upper + 1) as u32);
-            indices.push((lower + 1) as u32);
-        }
-    }
-
-    Mesh { vertices, indices }
-}
@@ -0,0 +1,744 @@
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use glam::{Vec2, Vec3};
+
+use crate::graphics::mesh::{Mesh, MeshVertex};
+
+/// A point on the profile of a surface of revolution around the y axis, from top to bottom.
+#[derive(Clone, Copy)]
+struct ProfilePoint {
+    radius: f32,
+    y: f32,
+    /// The normal in the `(radial, y)` plane.
+    normal: Vec2,
+    /// The v texture coordinate, decreasing from top to bottom.
+    v: f32,
+}
+
+/// Adds the triangles of a grid of `(rows + 1) * (cols + 1)` vertices starting at `base`,
+/// stored row by row. Triangles are counter-clockwise seen from the side that the cross product
+/// of the row direction and the column direction points to.
+///
+/// Triangles that would collapse into a point because all vertices of the first or last row
+/// coincide, as at the poles of a sphere, are left out.
+fn push_grid_indices(
+    mesh: &mut Mesh,
+    base: usize,
+    rows: usize,
+    cols: usize,
+    collapsed_first_row: bool,
+    collapsed_last_row: bool,
+) {
+    for row in 0..rows {
+        for col in 0..cols {
+            let upper = (base + row * (cols + 1) + col) as u32;
+            let lower = upper + cols as u32 + 1;
+            if !(row == 0 && collapsed_first_row) {
+                mesh.indices.extend([upper, lower, upper + 1]);
+            }
+            if !(row == rows - 1 && collapsed_last_row) {
+                mesh.indices.extend([lower, lower + 1, upper + 1]);
+            }
+        }
+    }
+}
+
+/// Revolves a profile around the y axis in `sectors` steps, starting at the positive z axis.
+fn push_revolution(mesh: &mut Mesh, profile: &[ProfilePoint], sectors: usize) {
+    let base = mesh.vertex_count();
+    for point in profile {
+        for sector in 0..=sectors {
+            let u = sector as f32 / sectors as f32;
+            let (sin, cos) = (u * TAU).sin_cos();
+            mesh.push_vertex(MeshVertex {
+                position: [point.radius * sin, point.y, point.radius * cos],
+                normal: Vec3::new(point.normal.x * sin, point.normal.y, point.normal.x * cos)
+                    .normalize()
+                    .to_array(),
+                tex_coords: [u, point.v],
+            });
+        }
+    }
+    push_grid_indices(
+        mesh,
+        base,
+        profile.len() - 1,
+        sectors,
+        profile[0].radius == 0.0,
+        profile[profile.len() - 1].radius == 0.0,
+    );
+}
+
+/// Adds a disk at height `y` facing up or down, with texture coordinates mapping the unit square
+/// onto it.
+fn push_disk(mesh: &mut Mesh, radius: f32, y: f32, up: bool, sectors: usize) {
+    let base = mesh.vertex_count() as u32;
+    let normal = if up {
+        [0.0, 1.0, 0.0]
+    } else {
+        [0.0, -1.0, 0.0]
+    };
+    mesh.push_vertex(MeshVertex {
+        position: [0.0, y, 0.0],
+        normal,
+        tex_coords: [0.5, 0.5],
+    });
+    for sector in 0..=sectors {
+        let (sin, cos) = (sector as f32 / sectors as f32 * TAU).sin_cos();
+        mesh.push_vertex(MeshVertex {
+            position: [radius * sin, y, radius * cos],
+            normal,
+            tex_coords: [0.5 + 0.5 * sin, 0.5 - 0.5 * cos],
+        });
+    }
+    for sector in 0..sectors as u32 {
+        let (first, second) = (base + 1 + sector, base + 2 + sector);
+        if up {
+            mesh.indices.extend([base, first, second]);
+        } else {
+            mesh.indices.extend([base, second, first]);
+        }
+    }
+}
+
+/// Adds a flat rectangle centered at `center` spanning `u` and `v`, facing `v.cross(u)`.
+fn push_rectangle(mesh: &mut Mesh, center: Vec3, u: Vec3, v: Vec3, subdivisions: usize) {
+    let base = mesh.vertex_count();
+    let segments = subdivisions + 1;
+    let normal = v.cross(u).normalize().to_array();
+    let origin = center - u / 2.0 - v / 2.0;
+    for row in 0..=segments {
+        for col in 0..=segments {
+            let (s, t) = (col as f32 / segments as f32, row as f32 / segments as f32);
+            mesh.push_vertex(MeshVertex {
+                position: (origin + u * s + v * t).to_array(),
+                normal,
+                tex_coords: [s, 1.0 - t],
+            });
+        }
+    }
+    push_grid_indices(mesh, base, segments, segments, false, false);
+}
+
+/// A sphere made of rings of latitude and sectors of longitude, centered at the origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UvSphere {
+    pub radius: f32,
+    /// The number of sectors of longitude, at least 3. Fewer are treated as 3.
+    pub sectors: usize,
+    /// The number of rings of latitude, at least 2. Fewer are treated as 2.
+    pub stacks: usize,
+}
+
+impl Default for UvSphere {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            sectors: 64,
+            stacks: 32,
+        }
+    }
+}
+
+impl UvSphere {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_sectors(mut self, sectors: usize) -> Self {
+        self.sectors = sectors.max(3);
+        self
+    }
+
+    pub fn with_stacks(mut self, stacks: usize) -> Self {
+        self.stacks = stacks.max(2);
+        self
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        let stacks = self.stacks.max(2);
+        let profile = (0..=stacks)
+            .map(|stack| {
+                let t = stack as f32 / stacks as f32;
+                let (sin, cos) = (t * PI).sin_cos();
+                ProfilePoint {
+                    // Exactly zero at the poles so that no degenerate triangles are added
+                    radius: if stack == 0 || stack == stacks {
+                        0.0
+                    } else {
+                        self.radius * sin
+                    },
+                    y: self.radius * cos,
+                    normal: Vec2::new(sin, cos),
+                    v: 1.0 - t,
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut mesh = Mesh::default();
+        push_revolution(&mut mesh, &profile, self.sectors.max(3));
+        mesh
+    }
+}
+
+/// A sphere made by subdividing an icosahedron, with more evenly sized triangles than a
+/// [`UvSphere`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Icosphere {
+    pub radius: f32,
+    /// How many times each triangle is split into four.
+    pub subdivisions: usize,
+}
+
+impl Default for Icosphere {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            subdivisions: 3,
+        }
+    }
+}
+
+impl Icosphere {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_subdivisions(mut self, subdivisions: usize) -> Self {
+        self.subdivisions = subdivisions;
+        self
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        let phi = (1.0 + 5.0_f32.sqrt()) / 2.0;
+        let mut points = [
+            [-1.0, phi, 0.0],
+            [1.0, phi, 0.0],
+            [-1.0, -phi, 0.0],
+            [1.0, -phi, 0.0],
+            [0.0, -1.0, phi],
+            [0.0, 1.0, phi],
+            [0.0, -1.0, -phi],
+            [0.0, 1.0, -phi],
+            [phi, 0.0, -1.0],
+            [phi, 0.0, 1.0],
+            [-phi, 0.0, -1.0],
+            [-phi, 0.0, 1.0],
+        ]
+        .map(|point| Vec3::from(point).normalize())
+        .to_vec();
+        let mut triangles: Vec<[usize; 3]> = vec![
+            [0, 11, 5],
+            [0, 5, 1],
+            [0, 1, 7],
+            [0, 7, 10],
+            [0, 10, 11],
+            [1, 5, 9],
+            [5, 11, 4],
+            [11, 10, 2],
+            [10, 7, 6],
+            [7, 1, 8],
+            [3, 9, 4],
+            [3, 4, 2],
+            [3, 2, 6],
+            [3, 6, 8],
+            [3, 8, 9],
+            [4, 9, 5],
+            [2, 4, 11],
+            [6, 2, 10],
+            [8, 6, 7],
+            [9, 8, 1],
+        ];
+
+        for _ in 0..self.subdivisions {
+            let mut midpoints = HashMap::new();
+            let mut midpoint = |a: usize, b: usize| {
+                *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    points.push(((points[a] + points[b]) / 2.0).normalize());
+                    points.len() - 1
+                })
+            };
+            triangles = triangles
+                .into_iter()
+                .flat_map(|[a, b, c]| {
+                    let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                    [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        // Spherical texture coordinates need duplicated vertices where triangles cross the seam
+        // at the negative z axis, and at the poles, where u depends on the triangle
+        let uv = |point: Vec3| {
+            Vec2::new(
+                0.5 + point.x.atan2(point.z) / TAU,
+                1.0 - point.y.clamp(-1.0, 1.0).acos() / PI,
+            )
+        };
+        let mut mesh = Mesh::default();
+        let mut vertex_indices = HashMap::new();
+        for triangle in triangles {
+            let mut uvs = triangle.map(|index| uv(points[index]));
+            let is_pole =
+                triangle.map(|index| points[index].x.abs() + points[index].z.abs() < 1e-6);
+            let max_u = uvs.iter().map(|uv| uv.x).fold(f32::MIN, f32::max);
+            for uv in &mut uvs {
+                if max_u - uv.x > 0.5 {
+                    uv.x += 1.0;
+                }
+            }
+            for corner in 0..3 {
+                if is_pole[corner] {
+                    let others = [(corner + 1) % 3, (corner + 2) % 3];
+                    uvs[corner].x = (uvs[others[0]].x + uvs[others[1]].x) / 2.0;
+                }
+            }
+            for (index, uv) in triangle.into_iter().zip(uvs) {
+                let key = (index, uv.x.to_bits());
+                let vertex_index = *vertex_indices.entry(key).or_insert_with(|| {
+                    mesh.push_vertex(MeshVertex {
+                        position: (points[index] * self.radius).to_array(),
+                        normal: points[index].to_array(),
+                        tex_coords: uv.to_array(),
+                    })
+                });
+                mesh.indices.push(vertex_index);
+            }
+        }
+        mesh
+    }
+}
+
+/// A cylinder along the y axis, centered at the origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cylinder {
+    pub radius: f32,
+    pub height: f32,
+    /// The number of sides, at least 3. Fewer are treated as 3.
+    pub resolution: usize,
+    /// The number of rings the side is split into along its height, at least 1.
+    pub segments: usize,
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            height: 1.0,
+            resolution: 32,
+            segments: 1,
+        }
+    }
+}
+
+impl Cylinder {
+    pub fn new(radius: f32, height: f32) -> Self {
+        Self {
+            radius,
+            height,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_resolution(mut self, resolution: usize) -> Self {
+        self.resolution = resolution.max(3);
+        self
+    }
+
+    pub fn with_segments(mut self, segments: usize) -> Self {
+        self.segments = segments.max(1);
+        self
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        let half_height = self.height / 2.0;
+        let (resolution, segments) = (self.resolution.max(3), self.segments.max(1));
+        let profile = (0..=segments)
+            .map(|segment| {
+                let t = segment as f32 / segments as f32;
+                ProfilePoint {
+                    radius: self.radius,
+                    y: half_height - t * self.height,
+                    normal: Vec2::X,
+                    v: 1.0 - t,
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut mesh = Mesh::default();
+        push_revolution(&mut mesh, &profile, resolution);
+        push_disk(&mut mesh, self.radius, half_height, true, resolution);
+        push_disk(&mut mesh, self.radius, -half_height, false, resolution);
+        mesh
+    }
+}
+
+/// A cone along the y axis with its apex at the top, centered at the origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cone {
+    pub radius: f32,
+    pub height: f32,
+    /// The number of sides, at least 3. Fewer are treated as 3.
+    pub resolution: usize,
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            height: 1.0,
+            resolution: 32,
+        }
+    }
+}
+
+impl Cone {
+    pub fn new(radius: f32, height: f32) -> Self {
+        Self {
+            radius,
+            height,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_resolution(mut self, resolution: usize) -> Self {
+        self.resolution = resolution.max(3);
+        self
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        let half_height = self.height / 2.0;
+        let resolution = self.resolution.max(3);
+        let normal = Vec2::new(self.height, self.radius).normalize();
+        let profile = [
+            ProfilePoint {
+                radius: 0.0,
+                y: half_height,
+                normal,
+                v: 1.0,
+            },
+            ProfilePoint {
+                radius: self.radius,
+                y: -half_height,
+                normal,
+                v: 0.0,
+            },
+        ];
+        let mut mesh = Mesh::default();
+        push_revolution(&mut mesh, &profile, resolution);
+        push_disk(&mut mesh, self.radius, -half_height, false, resolution);
+        mesh
+    }
+}
+
+/// A ring around the y axis, centered at the origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Torus {
+    /// The distance from the center to the middle of the tube.
+    pub major_radius: f32,
+    /// The radius of the tube.
+    pub minor_radius: f32,
+    /// The number of segments around the y axis, at least 3. Fewer are treated as 3.
+    pub major_segments: usize,
+    /// The number of segments around the tube, at least 3. Fewer are treated as 3.
+    pub minor_segments: usize,
+}
+
+impl Default for Torus {
+    fn default() -> Self {
+        Self {
+            major_radius: 0.375,
+            minor_radius: 0.125,
+            major_segments: 48,
+            minor_segments: 24,
+        }
+    }
+}
+
+impl Torus {
+    pub fn new(major_radius: f32, minor_radius: f32) -> Self {
+        Self {
+            major_radius,
+            minor_radius,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_segments(mut self, major_segments: usize, minor_segments: usize) -> Self {
+        self.major_segments = major_segments.max(3);
+        self.minor_segments = minor_segments.max(3);
+        self
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        // Around the tube from the outer equator, under the bottom, through the hole and back
+        let minor_segments = self.minor_segments.max(3);
+        let profile = (0..=minor_segments)
+            .map(|segment| {
+                let t = segment as f32 / minor_segments as f32;
+                let (sin, cos) = (t * TAU).sin_cos();
+                ProfilePoint {
+                    radius: self.major_radius + self.minor_radius * cos,
+                    y: -self.minor_radius * sin,
+                    normal: Vec2::new(cos, -sin),
+                    v: 1.0 - t,
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut mesh = Mesh::default();
+        push_revolution(&mut mesh, &profile, self.major_segments.max(3));
+        mesh
+    }
+}
+
+/// A cylinder with hemispherical ends along the y axis, centered at the origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capsule {
+    pub radius: f32,
+    /// The length of the cylindrical part, so the total height is `length + 2 * radius`.
+    pub length: f32,
+    /// The number of sectors of longitude, at least 3. Fewer are treated as 3.
+    pub sectors: usize,
+    /// The number of rings of latitude of each hemisphere, at least 1.
+    pub latitudes: usize,
+}
+
+impl Default for Capsule {
+    fn default() -> Self {
+        Self {
+            radius: 0.25,
+            length: 0.5,
+            sectors: 32,
+            latitudes: 8,
+        }
+    }
+}
+
+impl Capsule {
+    pub fn new(radius: f32, length: f32) -> Self {
+        Self {
+            radius,
+            length,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_sectors(mut self, sectors: usize) -> Self {
+        self.sectors = sectors.max(3);
+        self
+    }
+
+    pub fn with_latitudes(mut self, latitudes: usize) -> Self {
+        self.latitudes = latitudes.max(1);
+        self
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        let half_length = self.length / 2.0;
+        // v follows the length of the profile
+        let total = PI * self.radius + self.length;
+        let point = |angle: f32, center: f32, arc: f32| {
+            let (sin, cos) = angle.sin_cos();
+            ProfilePoint {
+                radius: if angle == 0.0 || angle == PI {
+                    0.0
+                } else {
+                    self.radius * sin
+                },
+                y: center + self.radius * cos,
+                normal: Vec2::new(sin, cos),
+                v: 1.0 - arc / total,
+            }
+        };
+        let latitudes = self.latitudes.max(1);
+        let mut profile = Vec::with_capacity(2 * latitudes + 2);
+        for latitude in 0..=latitudes {
+            let angle = FRAC_PI_2 * latitude as f32 / latitudes as f32;
+            profile.push(point(angle, half_length, angle * self.radius));
+        }
+        for latitude in 0..=latitudes {
+            let angle = FRAC_PI_2 + FRAC_PI_2 * latitude as f32 / latitudes as f32;
+            profile.push(point(
+                angle,
+                -half_length,
+                angle * self.radius + self.length,
+            ));
+        }
+        let mut mesh = Mesh::default();
+        push_revolution(&mut mesh, &profile, self.sectors.max(3));
+        mesh
+    }
+}
+
+/// A flat rectangle in the xz plane facing up, centered at the origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    /// The extents along the x and z axes.
+    pub size: Vec2,
+    /// The number of extra rows and columns of vertices, e.g. for displacement.
+    pub subdivisions: usize,
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Self {
+            size: Vec2::ONE,
+            subdivisions: 0,
+        }
+    }
+}
+
+impl Plane {
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            size,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_subdivisions(mut self, subdivisions: usize) -> Self {
+        self.subdivisions = subdivisions;
+        self
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        let mut mesh = Mesh::default();
+        push_rectangle(
+            &mut mesh,
+            Vec3::ZERO,
+            Vec3::X * self.size.x,
+            Vec3::Z * self.size.y,
+            self.subdivisions,
+        );
+        mesh
+    }
+}
+
+/// A box centered at the origin, with separate vertices for each face.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cuboid {
+    pub size: Vec3,
+    /// The number of extra rows and columns of vertices on each face.
+    pub subdivisions: usize,
+}
+
+impl Default for Cuboid {
+    fn default() -> Self {
+        Self {
+            size: Vec3::ONE,
+            subdivisions: 0,
+        }
+    }
+}
+
+impl Cuboid {
+    pub fn new(size: Vec3) -> Self {
+        Self {
+            size,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_subdivisions(mut self, subdivisions: usize) -> Self {
+        self.subdivisions = subdivisions;
+        self
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        let mut mesh = Mesh::default();
+        // Each face's u axis points right when looking at it from outside
+        for (normal, u) in [
+            (Vec3::Z, Vec3::X),
+            (Vec3::X, Vec3::NEG_Z),
+            (Vec3::NEG_Z, Vec3::NEG_X),
+            (Vec3::NEG_X, Vec3::Z),
+            (Vec3::Y, Vec3::X),
+            (Vec3::NEG_Y, Vec3::X),
+        ] {
+            let v = u.cross(normal);
+            push_rectangle(
+                &mut mesh,
+                normal * self.size / 2.0,
+                u * self.size,
+                v * self.size,
+                self.subdivisions,
+            );
+        }
+        mesh
+    }
+}
+
+macro_rules! impl_from_shape {
+    ($($shape:ty),*) => {
+        $(impl From<$shape> for Mesh {
+            fn from(shape: $shape) -> Self {
+                shape.mesh()
+            }
+        })*
+    };
+}
+
+impl_from_shape!(UvSphere, Icosphere, Cylinder, Cone, Torus, Capsule, Plane, Cuboid);
+
+#[test]
+fn test_shapes() {
+    assert_eq!(UvSphere::default().with_sectors(0).sectors, 3);
+    assert_eq!(Torus::default().with_segments(1, 0).minor_segments, 3);
+
+    let meshes: [Mesh; 11] = [
+        UvSphere::default().with_sectors(8).with_stacks(6).into(),
+        Icosphere::default().with_subdivisions(2).into(),
+        Cylinder::default().with_segments(3).into(),
+        Cone::default().into(),
+        Torus::default().into(),
+        Capsule::default().into(),
+        Plane::new(Vec2::new(2.0, 3.0)).with_subdivisions(2).into(),
+        Cuboid::new(Vec3::new(1.0, 2.0, 3.0))
+            .with_subdivisions(1)
+            .into(),
+        // Too few sides or rings are clamped rather than producing NaNs
+        UvSphere {
+            sectors: 0,
+            stacks: 0,
+            ..Default::default()
+        }
+        .into(),
+        Cylinder::default()
+            .with_resolution(1)
+            .with_segments(0)
+            .into(),
+        Capsule {
+            sectors: 1,
+            latitudes: 0,
+            ..Default::default()
+        }
+        .into(),
+    ];
+    for mesh in meshes {
+        assert_eq!(mesh.indices.len() % 3, 0);
+        mesh.validate().unwrap();
+        let vertices = (0..mesh.vertex_count())
+            .map(|index| mesh.vertex(index))
+            .collect::<Vec<_>>();
+        for vertex in &vertices {
+            assert!((Vec3::from(vertex.normal).length() - 1.0).abs() < 1e-4);
+            // Vertices on the seam of an icosphere wrap around past u = 1
+            let [u, v] = vertex.tex_coords;
+            assert!((0.0..2.0).contains(&u) && (-1e-6..=1.0 + 1e-6).contains(&v));
+        }
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| vertices[triangle[i] as usize]);
+            let [pa, pb, pc] = [a, b, c].map(|vertex| Vec3::from(vertex.position));
+            let face_normal = (pb - pa).cross(pc - pa);
+            assert!(face_normal.length() > 0.0, "degenerate triangle");
+            // Counter-clockwise seen from outside, i.e. along the vertex normals
+            let vertex_normal = Vec3::from(a.normal) + Vec3::from(b.normal) + Vec3::from(c.normal);
+            assert!(face_normal.dot(vertex_normal) > 0.0);
+        }
+    }
+}
@@ -1,2 +1,146 @@
+use std::time::Duration;
+
+/// The time since the previous frame in seconds, for use as `dt` in
+/// [`Update`](crate::app::SystemStage::Update) systems. It follows the wall time between frames,
+/// or the recorded time step while replaying input.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Time(pub f64);
+
+/// Clock for systems in the [`FixedUpdate`](crate::app::SystemStage::FixedUpdate) stage.
+///
+/// Every frame the elapsed wall time is added to an accumulator, and the fixed systems are run
+/// once for every whole `step` in it. At most `max_steps` are run per frame; time beyond that is
+/// dropped so that a slow frame cannot snowball into ever slower frames.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedTime {
+    step: Duration,
+    max_steps: u32,
+    accumulator: Duration,
+    steps_this_frame: u32,
+    elapsed: Duration,
+    tick: u64,
+}
+
+impl FixedTime {
+    /// The default number of steps run per second.
+    pub const DEFAULT_HZ: f64 = 60.0;
+    /// The default maximum number of steps run in a single frame.
+    pub const DEFAULT_MAX_STEPS: u32 = 5;
+
+    pub fn new(step: Duration) -> Self {
+        assert!(!step.is_zero(), "fixed timestep must be non-zero");
+        Self {
+            step,
+            max_steps: Self::DEFAULT_MAX_STEPS,
+            accumulator: Duration::ZERO,
+            steps_this_frame: 0,
+            elapsed: Duration::ZERO,
+            tick: 0,
+        }
+    }
+
+    /// Creates a fixed clock that steps `hz` times per second.
+    pub fn from_hz(hz: f64) -> Self {
+        assert!(hz > 0.0 && hz.is_finite(), "hz must be positive");
+        Self::new(Duration::from_secs_f64(1.0 / hz))
+    }
+
+    /// Sets the maximum number of steps that may run in a single frame.
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        assert!(max_steps > 0, "max_steps must be at least 1");
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// The length of a single step.
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// The length of a single step in seconds, for use as `dt` in fixed systems.
+    pub fn delta_seconds(&self) -> f32 {
+        self.step.as_secs_f32()
+    }
+
+    /// The total simulated time, i.e. the number of steps run multiplied by the step length.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The number of steps run since the app started.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// How far the accumulator is into the next step, in `[0, 1)`.
+    ///
+    /// Rendering can use this to interpolate between the previous and current simulation state.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f64() / self.step.as_secs_f64()) as f32
+    }
+
+    /// Adds a frame's worth of wall time to the accumulator.
+    pub fn accumulate(&mut self, delta: Duration) {
+        self.accumulator += delta;
+        self.steps_this_frame = 0;
+    }
+
+    /// Consumes one step from the accumulator, returning whether a step should be run.
+    ///
+    /// Once `max_steps` have been consumed in a frame, any whole steps left in the accumulator
+    /// are discarded.
+    pub fn expend(&mut self) -> bool {
+        if self.accumulator < self.step {
+            return false;
+        }
+        if self.steps_this_frame >= self.max_steps {
+            let step = self.step.as_nanos();
+            let remainder = self.accumulator.as_nanos() % step;
+            self.accumulator = Duration::from_nanos(remainder as u64);
+            return false;
+        }
+        self.accumulator -= self.step;
+        self.steps_this_frame += 1;
+        self.elapsed += self.step;
+        self.tick += 1;
+        true
+    }
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::from_hz(Self::DEFAULT_HZ)
+    }
+}
+
+/// The [`FixedTime::alpha`] of the current frame, updated after the fixed systems have run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InterpolationAlpha(pub f32);
+
+#[test]
+fn fixed_time_caps_steps() {
+    let mut fixed_time = FixedTime::new(Duration::from_millis(10)).with_max_steps(3);
+
+    fixed_time.accumulate(Duration::from_millis(25));
+    let mut steps = 0;
+    while fixed_time.expend() {
+        steps += 1;
+    }
+    assert_eq!(steps, 2);
+    assert!((fixed_time.alpha() - 0.5).abs() < 1e-6);
+
+    fixed_time.accumulate(Duration::from_millis(100));
+    let mut steps = 0;
+    while fixed_time.expend() {
+        steps += 1;
+    }
+    assert_eq!(steps, 3);
+    assert_eq!(fixed_time.tick(), 5);
+    assert!((fixed_time.alpha() - 0.5).abs() < 1e-6);
+}
+
+#[test]
+#[should_panic(expected = "hz must be positive")]
+fn fixed_time_rejects_invalid_hz() {
+    FixedTime::from_hz(0.0);
+}
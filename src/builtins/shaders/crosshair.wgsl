@@ -12,7 +12,9 @@ fn vs_main(@builtin(vertex_index) vertex_index : u32) -> VertexOutput {
   );
 
   var output: VertexOutput;
-  output.position = vec4<f32>(positions[vertex_index], 0.0, 1.0);
+  // Halfway into the freshly cleared depth buffer, so that it passes the depth test in either
+  // depth mode
+  output.position = vec4<f32>(positions[vertex_index], 0.5, 1.0);
   output.fragCoord = output.position;
   return output;
 }
@@ -4,6 +4,8 @@ struct CameraUniform {
 };
 struct ModelUniform {
     model: mat4x4<f32>,
+    // Inverse transpose of the model matrix's upper 3x3, so normals survive non-uniform scale
+    normal: mat4x4<f32>,
 };
 struct LambertMaterialUniform {
     ambient: vec3<f32>,
@@ -36,7 +38,7 @@ fn vs_main(
 ) -> VertexOutput {
     var out: VertexOutput;
     out.world_position = model.model * vec4<f32>(input.position, 1.0);
-    out.world_normal = normalize(model.model * vec4<f32>(input.normal, 0.0));
+    out.world_normal = vec4<f32>(normalize((model.normal * vec4<f32>(input.normal, 0.0)).xyz), 0.0);
     out.tex_coords = input.tex_coords;
     out.clip_position = camera.view_proj * out.world_position;
     return out;
@@ -1,4 +1,13 @@
+use thiserror::Error;
+
 pub mod camera;
+pub mod hierarchy;
 pub mod render;
+pub mod visibility;
 
 pub struct ActiveCamera;
+
+/// Returned by built-in systems that need an [`ActiveCamera`] when there is none.
+#[derive(Debug, Error)]
+#[error("No active camera found")]
+pub struct NoActiveCamera;
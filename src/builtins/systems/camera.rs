@@ -1,91 +1,207 @@
-use glam::Mat4;
-use winit::keyboard::Key;
+use glam::{EulerRot, Quat, Vec3};
+use winit::keyboard::KeyCode;
 
 use crate::{
-    builtins::systems::ActiveCamera,
-    ecs::{query::Query, resource::ResMut, World},
-    graphics::{camera::CameraTransform, PerspectiveCamera, Transform},
-    input::InputState,
+    builtins::{resources::time::Time, systems::ActiveCamera},
+    ecs::{query::Query, resource::Res, World},
+    graphics::{camera::CameraTransform, Camera, PerspectiveCamera, Projection},
+    input::{Binding, InputMap},
 };
 
-pub fn init_camera_system(world: &mut World) {
-    let camera = PerspectiveCamera::new(90.0, 800_f32 / 600_f32, 0.1, 100.0);
-    let camera_transform = CameraTransform::from(&Transform::from(
-        Mat4::look_to_rh(
-            glam::Vec3::new(0.0, 0.0, 0.0),
-            glam::Vec3::new(0.0, 0.0, -1.0),
-            glam::Vec3::new(0.0, 1.0, 0.0),
-        )
-        .inverse(),
-    ));
+pub use orbit::{update_orbit_camera_system, OrbitCamera};
+pub use path::{update_camera_path_system, CameraKeyframe, CameraPath, Easing, PathInterpolation};
 
-    let camera_entity = world.new_entity();
-    world.add_component_to_entity::<PerspectiveCamera>(camera_entity, camera);
-    world.add_component_to_entity::<CameraTransform>(camera_entity, camera_transform);
-    world.add_component_to_entity::<ActiveCamera>(camera_entity, ActiveCamera);
+mod orbit;
+mod path;
+
+/// Actions that drive [`update_camera_system`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlyCameraAction {
+    /// Positive moves forward, negative moves backward.
+    MoveForward,
+    /// Positive moves right, negative moves left.
+    MoveRight,
+    /// Positive moves up, negative moves down, along the world y axis.
+    MoveUp,
+    /// Multiplies the speed by [`FlyCamera::sprint_multiplier`] while pressed.
+    Sprint,
+    /// Positive turns right.
+    LookHorizontal,
+    /// Positive looks down.
+    LookVertical,
 }
 
-pub fn update_camera_system(
-    mut input_state: ResMut<InputState>,
-    mut camera: Query<(&mut CameraTransform, &ActiveCamera)>,
-) {
-    let (transform, _) = (&mut camera)
-        .into_iter()
-        .next()
-        .expect("No active camera found");
+impl FlyCameraAction {
+    /// WASD movement, space and left control to move up and down, left shift to sprint and mouse
+    /// look.
+    pub fn default_input_map() -> InputMap<Self> {
+        InputMap::new()
+            .with_binding(
+                Self::MoveForward,
+                Binding::KeyAxis {
+                    negative: KeyCode::KeyS,
+                    positive: KeyCode::KeyW,
+                },
+            )
+            .with_binding(
+                Self::MoveRight,
+                Binding::KeyAxis {
+                    negative: KeyCode::KeyA,
+                    positive: KeyCode::KeyD,
+                },
+            )
+            .with_binding(
+                Self::MoveUp,
+                Binding::KeyAxis {
+                    negative: KeyCode::ControlLeft,
+                    positive: KeyCode::Space,
+                },
+            )
+            .with_binding(Self::Sprint, KeyCode::ShiftLeft)
+            .with_binding(Self::LookHorizontal, Binding::MouseMotionX)
+            .with_binding(Self::LookVertical, Binding::MouseMotionY)
+    }
+}
 
-    const SPEED: f32 = 0.05;
-    let mut dir = glam::Vec4::ZERO;
+/// Component with the settings of a first-person camera driven by [`update_camera_system`].
+#[derive(Debug, Clone, Copy)]
+pub struct FlyCamera {
+    /// Movement speed in units per second.
+    pub speed: f32,
+    /// Factor applied to `speed` while [`FlyCameraAction::Sprint`] is pressed.
+    pub sprint_multiplier: f32,
+    /// Radians of rotation per unit of [`FlyCameraAction::LookHorizontal`] and
+    /// [`FlyCameraAction::LookVertical`], i.e. per pixel of mouse motion by default.
+    pub sensitivity: f32,
+    /// Whether moving the mouse up looks down.
+    pub invert_y: bool,
+    /// The lowest the camera can look, in radians below the horizon (negative).
+    pub min_pitch: f32,
+    /// The highest the camera can look, in radians above the horizon.
+    pub max_pitch: f32,
+}
 
-    if input_state.keys.contains(&Key::Character("w".into())) {
-        dir += -transform.0.z_axis;
+impl Default for FlyCamera {
+    fn default() -> Self {
+        Self {
+            speed: 3.0,
+            sprint_multiplier: 3.0,
+            sensitivity: 0.001,
+            invert_y: false,
+            min_pitch: -89_f32.to_radians(),
+            max_pitch: 89_f32.to_radians(),
+        }
     }
-    if input_state.keys.contains(&Key::Character("s".into())) {
-        dir += transform.0.z_axis;
+}
+
+impl FlyCamera {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
     }
-    if input_state.keys.contains(&Key::Character("a".into())) {
-        dir += -transform.0.x_axis;
+
+    pub fn with_sprint_multiplier(mut self, sprint_multiplier: f32) -> Self {
+        self.sprint_multiplier = sprint_multiplier;
+        self
     }
-    if input_state.keys.contains(&Key::Character("d".into())) {
-        dir += transform.0.x_axis;
+
+    pub fn with_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.sensitivity = sensitivity;
+        self
     }
 
-    dir = dir.normalize_or_zero();
+    pub fn with_invert_y(mut self, invert_y: bool) -> Self {
+        self.invert_y = invert_y;
+        self
+    }
 
-    transform.0.w_axis += dir * SPEED;
+    pub fn with_pitch_limits(mut self, min_pitch: f32, max_pitch: f32) -> Self {
+        self.min_pitch = min_pitch;
+        self.max_pitch = max_pitch;
+        self
+    }
+}
 
-    const SENSITIVITY: f32 = 0.001;
-    let (dx, dy) = (-input_state.mouse_delta.0, -input_state.mouse_delta.1);
+pub fn init_camera_system(world: &mut World) {
+    let camera = PerspectiveCamera::new(90.0, 800_f32 / 600_f32, 0.1, 100.0);
+    let camera_transform =
+        CameraTransform::from_xyz(0.0, 0.0, 0.0).looking_at(Vec3::NEG_Z, Vec3::Y);
 
-    // first-person controls
-    let (scale, rot, trans) = transform.0.to_scale_rotation_translation();
-    let horizontal_rotation = glam::Quat::from_axis_angle(glam::Vec3::Y, dx as f32 * SENSITIVITY);
-    let mut vertical_rotation =
-        glam::Quat::from_axis_angle(transform.0.x_axis.truncate(), dy as f32 * SENSITIVITY);
-    let new_z = vertical_rotation * transform.0.z_axis.truncate();
+    let camera_entity = world.new_entity();
+    world.add_component_to_entity::<Projection>(camera_entity, camera.into());
+    world.add_component_to_entity::<CameraTransform>(camera_entity, camera_transform);
+    world.add_component_to_entity::<Camera>(camera_entity, Camera::default());
+    world.add_component_to_entity::<ActiveCamera>(camera_entity, ActiveCamera);
+    world.add_component_to_entity::<FlyCamera>(camera_entity, FlyCamera::default());
+}
 
-    if new_z.dot(transform.0.x_axis.truncate().cross(glam::Vec3::Y)) < 0.0 {
-        let dir = if -new_z.y > 0.0 {
-            glam::Vec3::Y
+/// Moves and turns every active camera with a [`FlyCamera`] according to its settings, except
+/// while it is animated by a playing [`CameraPath`].
+pub fn update_camera_system(
+    input_map: Res<InputMap<FlyCameraAction>>,
+    time: Res<Time>,
+    mut cameras: Query<(
+        &FlyCamera,
+        &mut CameraTransform,
+        &ActiveCamera,
+        Option<&CameraPath>,
+    )>,
+) {
+    for (fly_camera, transform, _, path) in &mut cameras {
+        if path.is_some_and(CameraPath::is_playing) {
+            continue;
+        }
+        // Mouse motion is already per frame, so looking is not scaled by delta time
+        let (mut yaw, mut pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        let look_vertical = input_map.axis_value(FlyCameraAction::LookVertical);
+        let look_vertical = if fly_camera.invert_y {
+            -look_vertical
         } else {
-            glam::Vec3::NEG_Y
+            look_vertical
         };
-        vertical_rotation = glam::Quat::from_rotation_arc(-transform.0.z_axis.truncate(), dir)
+        yaw -= input_map.axis_value(FlyCameraAction::LookHorizontal) * fly_camera.sensitivity;
+        pitch = (pitch - look_vertical * fly_camera.sensitivity)
+            .clamp(fly_camera.min_pitch, fly_camera.max_pitch);
+        transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+
+        let mut speed = fly_camera.speed;
+        if input_map.pressed(FlyCameraAction::Sprint) {
+            speed *= fly_camera.sprint_multiplier;
+        }
+        let dir = transform.forward() * input_map.axis_value(FlyCameraAction::MoveForward)
+            + transform.right() * input_map.axis_value(FlyCameraAction::MoveRight)
+            + Vec3::Y * input_map.axis_value(FlyCameraAction::MoveUp);
+        transform.translate(dir.normalize_or_zero() * speed * time.0 as f32);
     }
+}
 
-    let new_transform = glam::Mat4::from_scale_rotation_translation(
-        scale,
-        horizontal_rotation * vertical_rotation * rot,
-        trans,
-    );
+#[test]
+fn test_fly_camera_pitch_clamp() {
+    use crate::{
+        ecs::system::{IntoSystem, System},
+        input::InputState,
+    };
+    let mut world = World::new();
+    world.add_resource(Time(0.5));
+    let mut input = InputState::default();
+    input.key_codes.press(KeyCode::KeyW);
+    input.mouse_delta = (0.0, -10_000.0);
+    let mut input_map = FlyCameraAction::default_input_map();
+    input_map.update(&input);
+    world.add_resource(input_map);
 
-    transform.0 = new_transform;
+    let camera = world.new_entity();
+    world.add_component_to_entity(camera, CameraTransform::IDENTITY);
+    world.add_component_to_entity(camera, ActiveCamera);
+    world.add_component_to_entity(camera, FlyCamera::new().with_speed(2.0));
 
-    // third-person controls
-    // let horizontal_rotation = glam::Mat4::from_axis_angle(glam::Vec3::Y, dx as f32 * SENSITIVITY);
-    // let vertical_rotation =
-    //     glam::Mat4::from_axis_angle(transform.0.x_axis.truncate(), dy as f32 * SENSITIVITY);
-    // transform.0 = horizontal_rotation * vertical_rotation * transform.0;
+    update_camera_system.into_system().run(&mut world).unwrap();
 
-    input_state.mouse_delta = (0.0, 0.0);
+    let transform = world.borrow_component_vec::<CameraTransform>().unwrap()[camera].unwrap();
+    assert!((transform.forward().y.asin() - 89_f32.to_radians()).abs() < 1e-4);
+    assert!((transform.translation.length() - 1.0).abs() < 1e-4);
 }
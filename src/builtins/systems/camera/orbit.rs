@@ -0,0 +1,174 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glam::{Mat4, Vec3};
+use winit::event::MouseButton;
+
+use crate::{
+    builtins::resources::time::Time,
+    ecs::{query::Query, resource::Res},
+    graphics::{camera::CameraTransform, Transform},
+    input::InputState,
+};
+
+/// Component for a camera that orbits around a focus point, as in a model viewer.
+///
+/// [`update_orbit_camera_system`] orbits it while the left mouse button is dragged, zooms it with
+/// the scroll wheel and pans the focus point while the middle mouse button is dragged. The public
+/// fields can also be set directly, e.g. to frame a newly loaded model.
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitCamera {
+    /// The point the camera looks at.
+    pub focus: Vec3,
+    /// The distance from the focus point to the camera.
+    pub distance: f32,
+    /// The rotation around the focus point about the y axis, in radians. At `0` the camera looks
+    /// down the negative z axis.
+    pub yaw: f32,
+    /// The elevation of the camera above the focus point, in radians.
+    pub pitch: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    pub min_pitch: f32,
+    pub max_pitch: f32,
+    /// Radians of rotation per pixel of mouse motion.
+    pub orbit_sensitivity: f32,
+    /// The fraction of the distance zoomed per line scrolled.
+    pub zoom_sensitivity: f32,
+    /// The fraction of the distance panned per pixel of mouse motion.
+    pub pan_sensitivity: f32,
+    /// How quickly the camera catches up with the input, per second. `None` applies input
+    /// immediately.
+    pub damping: Option<f32>,
+    /// The smoothed `(focus, distance, yaw, pitch)` while damping.
+    smoothed: Option<(Vec3, f32, f32, f32)>,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            focus: Vec3::ZERO,
+            distance: 5.0,
+            yaw: 0.0,
+            pitch: 0.0,
+            min_distance: 0.1,
+            max_distance: 1000.0,
+            min_pitch: -FRAC_PI_2 + 0.01,
+            max_pitch: FRAC_PI_2 - 0.01,
+            orbit_sensitivity: 0.005,
+            zoom_sensitivity: 0.1,
+            pan_sensitivity: 0.001,
+            damping: None,
+            smoothed: None,
+        }
+    }
+}
+
+impl OrbitCamera {
+    pub fn new(focus: Vec3, distance: f32) -> Self {
+        Self {
+            focus,
+            distance,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_angles(mut self, yaw: f32, pitch: f32) -> Self {
+        self.yaw = yaw;
+        self.pitch = pitch;
+        self
+    }
+
+    pub fn with_distance_limits(mut self, min_distance: f32, max_distance: f32) -> Self {
+        self.min_distance = min_distance;
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn with_pitch_limits(mut self, min_pitch: f32, max_pitch: f32) -> Self {
+        self.min_pitch = min_pitch;
+        self.max_pitch = max_pitch;
+        self
+    }
+
+    /// Smooths the motion of the camera, catching up with the input at `rate` per second.
+    pub fn with_damping(mut self, rate: f32) -> Self {
+        self.damping = Some(rate);
+        self
+    }
+
+    /// The camera-to-world matrix for the given orbit parameters.
+    fn transform(focus: Vec3, distance: f32, yaw: f32, pitch: f32) -> Mat4 {
+        let offset = Vec3::new(
+            -yaw.sin() * pitch.cos(),
+            pitch.sin(),
+            yaw.cos() * pitch.cos(),
+        );
+        Mat4::look_at_rh(focus + offset * distance, focus, Vec3::Y).inverse()
+    }
+
+    /// Advances the smoothed state towards the current parameters and returns the camera-to-world
+    /// matrix.
+    fn update(&mut self, dt: f32) -> Mat4 {
+        let target = (self.focus, self.distance, self.yaw, self.pitch);
+        let (focus, distance, yaw, pitch) = match (self.damping, self.smoothed) {
+            (Some(rate), Some((focus, distance, yaw, pitch))) => {
+                let t = 1.0 - (-rate * dt).exp();
+                (
+                    focus.lerp(target.0, t),
+                    distance + (target.1 - distance) * t,
+                    yaw + (target.2 - yaw) * t,
+                    pitch + (target.3 - pitch) * t,
+                )
+            }
+            _ => target,
+        };
+        self.smoothed = Some((focus, distance, yaw, pitch));
+        Self::transform(focus, distance, yaw, pitch)
+    }
+}
+
+/// Drives the [`CameraTransform`] of every entity with an [`OrbitCamera`] from the mouse.
+pub fn update_orbit_camera_system(
+    input: Res<InputState>,
+    time: Res<Time>,
+    mut cameras: Query<(&mut OrbitCamera, &mut CameraTransform)>,
+) {
+    let (dx, dy) = (input.mouse_delta.0 as f32, input.mouse_delta.1 as f32);
+    let scroll = input.scroll_lines();
+
+    for (orbit, transform) in &mut cameras {
+        if input.mouse_buttons.pressed(MouseButton::Left) {
+            orbit.yaw -= dx * orbit.orbit_sensitivity;
+            orbit.pitch += dy * orbit.orbit_sensitivity;
+        }
+        if input.mouse_buttons.pressed(MouseButton::Middle) {
+            let right = transform.right();
+            let up = transform.up();
+            let pan = orbit.pan_sensitivity * orbit.distance;
+            orbit.focus += (up * dy - right * dx) * pan;
+        }
+        orbit.distance *= (1.0 - orbit.zoom_sensitivity).powf(scroll);
+
+        orbit.distance = orbit.distance.clamp(orbit.min_distance, orbit.max_distance);
+        orbit.pitch = orbit.pitch.clamp(orbit.min_pitch, orbit.max_pitch);
+        *transform = Transform::from(orbit.update(time.0 as f32));
+    }
+}
+
+#[test]
+fn test_orbit_camera() {
+    let mut orbit = OrbitCamera::new(Vec3::new(1.0, 0.0, 0.0), 2.0);
+    let transform = orbit.update(0.0);
+    assert!(transform
+        .w_axis
+        .truncate()
+        .abs_diff_eq(Vec3::new(1.0, 0.0, 2.0), 1e-5));
+    assert!((-transform.z_axis.truncate()).abs_diff_eq(Vec3::NEG_Z, 1e-5));
+
+    orbit.pitch = FRAC_PI_2 / 2.0;
+    orbit.damping = Some(10.0);
+    let halfway = orbit.update(0.1).w_axis.truncate();
+    let target = orbit.update(10.0).w_axis.truncate();
+    assert!(halfway.y > 0.0 && halfway.y < target.y);
+    assert!((target.y - 2.0 * (FRAC_PI_2 / 2.0).sin()).abs() < 1e-4);
+}
@@ -0,0 +1,411 @@
+use glam::{Quat, Vec3};
+
+use crate::{
+    builtins::resources::time::Time,
+    ecs::world::World,
+    graphics::{camera::CameraTransform, Projection, Transform},
+};
+
+/// Curves that map the progress through a segment of a [`CameraPath`] from `[0, 1]` to `[0, 1]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// Starts slowly and speeds up (quadratic).
+    EaseIn,
+    /// Starts quickly and slows down (quadratic).
+    EaseOut,
+    /// Starts and ends slowly (cubic).
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// How a [`CameraPath`] interpolates positions between keyframes. Rotations are always slerped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathInterpolation {
+    /// Straight lines between keyframes.
+    Linear,
+    /// A smooth curve through every keyframe.
+    #[default]
+    CatmullRom,
+    /// Cubic Bezier curves between keyframes, shaped by the keyframes'
+    /// [handles](CameraKeyframe::with_handles). Keyframes without handles get the ones that match
+    /// [`PathInterpolation::CatmullRom`].
+    Bezier,
+}
+
+/// A pose of the camera at a point in time along a [`CameraPath`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraKeyframe {
+    /// The time in seconds since the start of the path.
+    pub time: f32,
+    pub position: Vec3,
+    pub rotation: Quat,
+    /// The horizontal field of view in degrees, or `None` to leave it unchanged.
+    pub fov: Option<f32>,
+    /// The easing of the segment from this keyframe to the next.
+    pub easing: Easing,
+    /// The Bezier control points `(incoming, outgoing)` of this keyframe.
+    pub handles: Option<(Vec3, Vec3)>,
+}
+
+impl CameraKeyframe {
+    pub fn new(time: f32, position: Vec3, rotation: Quat) -> Self {
+        Self {
+            time,
+            position,
+            rotation,
+            fov: None,
+            easing: Easing::Linear,
+            handles: None,
+        }
+    }
+
+    /// A keyframe at `position` looking towards `target` with the y axis up.
+    pub fn looking_at(time: f32, position: Vec3, target: Vec3) -> Self {
+        let rotation = Transform::from_translation(position)
+            .looking_at(target, Vec3::Y)
+            .rotation;
+        Self::new(time, position, rotation)
+    }
+
+    pub fn with_fov(mut self, fov: f32) -> Self {
+        self.fov = Some(fov);
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn with_handles(mut self, incoming: Vec3, outgoing: Vec3) -> Self {
+        self.handles = Some((incoming, outgoing));
+        self
+    }
+}
+
+/// A one-shot transition whose first keyframe is taken from the camera when it starts playing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Transition {
+    MoveTo {
+        position: Vec3,
+        rotation: Option<Quat>,
+    },
+    LookAt {
+        target: Vec3,
+    },
+}
+
+/// Component that animates a camera along keyframes, e.g. for cutscenes.
+///
+/// While it plays, [`update_camera_path_system`] drives the entity's [`CameraTransform`] and the
+/// field of view of its [`PerspectiveCamera`](crate::graphics::PerspectiveCamera), and
+/// [`update_camera_system`](super::update_camera_system) leaves the camera alone. Once it has
+/// finished, the fly camera takes over from the last keyframe.
+///
+/// [`App::with_default_systems`](crate::app::App::with_default_systems) runs the path system
+/// after the fly camera system; when registering the systems by hand, keep that order so the path
+/// has the last word on the camera's pose.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraPath {
+    keyframes: Vec<CameraKeyframe>,
+    pub interpolation: PathInterpolation,
+    /// Whether to start over at the end instead of finishing.
+    pub looping: bool,
+    transition: Option<(Transition, f32, Easing)>,
+    /// Whether the keyframes of the transition have been resolved since it last started playing.
+    transition_started: bool,
+    elapsed: f32,
+    playing: bool,
+}
+
+impl CameraPath {
+    /// Creates a path that starts playing right away. The keyframes are sorted by time.
+    pub fn new(mut keyframes: Vec<CameraKeyframe>) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            keyframes,
+            interpolation: PathInterpolation::default(),
+            looping: false,
+            transition: None,
+            transition_started: false,
+            elapsed: 0.0,
+            playing: true,
+        }
+    }
+
+    /// Moves the camera from wherever it is to `position` over `duration` seconds, turning it to
+    /// `rotation` if given.
+    pub fn move_to(position: Vec3, rotation: Option<Quat>, duration: f32) -> Self {
+        Self::transition(Transition::MoveTo { position, rotation }, duration)
+    }
+
+    /// Turns the camera in place to look at `target` over `duration` seconds.
+    pub fn look_at(target: Vec3, duration: f32) -> Self {
+        Self::transition(Transition::LookAt { target }, duration)
+    }
+
+    fn transition(transition: Transition, duration: f32) -> Self {
+        Self {
+            interpolation: PathInterpolation::Linear,
+            transition: Some((transition, duration, Easing::EaseInOut)),
+            ..Self::new(Vec::new())
+        }
+    }
+
+    pub fn with_interpolation(mut self, interpolation: PathInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Sets the easing of every segment, including that of a transition.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        for keyframe in &mut self.keyframes {
+            keyframe.easing = easing;
+        }
+        if let Some((_, _, transition_easing)) = &mut self.transition {
+            *transition_easing = easing;
+        }
+        self
+    }
+
+    pub fn keyframes(&self) -> &[CameraKeyframe] {
+        &self.keyframes
+    }
+
+    /// The time of the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Plays the path from the start. A transition starts over from the camera's current pose.
+    pub fn play(&mut self) {
+        self.elapsed = 0.0;
+        self.transition_started = false;
+        self.playing = true;
+    }
+
+    /// Stops the path where it is, handing the camera back to the fly camera.
+    pub fn stop(&mut self) {
+        self.playing = false;
+    }
+
+    /// Returns the position, rotation and field of view at `time` seconds, or `None` if the path
+    /// has no keyframes.
+    pub fn sample(&self, time: f32) -> Option<(Vec3, Quat, Option<f32>)> {
+        let keyframes = &self.keyframes;
+        let index = keyframes
+            .iter()
+            .rposition(|keyframe| keyframe.time <= time)
+            .unwrap_or(0);
+        let from = keyframes.get(index)?;
+        let Some(to) = keyframes.get(index + 1).filter(|_| time > from.time) else {
+            return Some((from.position, from.rotation, from.fov));
+        };
+
+        let t = (time - from.time) / (to.time - from.time).max(f32::EPSILON);
+        let t = from.easing.apply(t);
+
+        let before = &keyframes[index.saturating_sub(1)];
+        let after = &keyframes[(index + 2).min(keyframes.len() - 1)];
+        let position = match self.interpolation {
+            PathInterpolation::Linear => from.position.lerp(to.position, t),
+            PathInterpolation::CatmullRom => {
+                let (p0, p1, p2, p3) =
+                    (before.position, from.position, to.position, after.position);
+                0.5 * (2.0 * p1
+                    + (p2 - p0) * t
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t * t
+                    + (3.0 * p1 - p0 - p2 + 3.0 * p3) * t * t * t)
+            }
+            PathInterpolation::Bezier => {
+                let outgoing = from.handles.map_or_else(
+                    || from.position + (to.position - before.position) / 6.0,
+                    |(_, outgoing)| outgoing,
+                );
+                let incoming = to.handles.map_or_else(
+                    || to.position - (after.position - from.position) / 6.0,
+                    |(incoming, _)| incoming,
+                );
+                let s = 1.0 - t;
+                from.position * s * s * s
+                    + outgoing * 3.0 * s * s * t
+                    + incoming * 3.0 * s * t * t
+                    + to.position * t * t * t
+            }
+        };
+        let rotation = from.rotation.slerp(to.rotation, t);
+        let fov = match (from.fov, to.fov) {
+            (Some(a), Some(b)) => Some(a + (b - a) * t),
+            (a, b) => a.or(b),
+        };
+        Some((position, rotation, fov))
+    }
+
+    /// Turns the transition into keyframes starting at the camera's current pose, unless that has
+    /// already happened since it last started playing.
+    fn start_transition(&mut self, current: &CameraTransform) {
+        let Some((transition, duration, easing)) = self.transition else {
+            return;
+        };
+        if self.transition_started {
+            return;
+        }
+        self.transition_started = true;
+        let (position, rotation) = (current.translation, current.rotation);
+        let end = match transition {
+            Transition::MoveTo {
+                position: target_position,
+                rotation: target_rotation,
+            } => CameraKeyframe::new(
+                duration,
+                target_position,
+                target_rotation.unwrap_or(rotation),
+            ),
+            Transition::LookAt { target } => CameraKeyframe::looking_at(duration, position, target),
+        };
+        self.keyframes = vec![
+            CameraKeyframe::new(0.0, position, rotation).with_easing(easing),
+            end,
+        ];
+    }
+}
+
+/// Advances every playing [`CameraPath`] and moves its entity along it. The field of view of the
+/// keyframes is applied only to entities with a perspective [`Projection`].
+pub fn update_camera_path_system(world: &mut World) {
+    let (Some(mut paths), Some(mut transforms), Some(time)) = (
+        world.borrow_component_vec_mut::<CameraPath>(),
+        world.borrow_component_vec_mut::<CameraTransform>(),
+        world.get_resource::<Time>(),
+    ) else {
+        return;
+    };
+    let mut projections = world.borrow_component_vec_mut::<Projection>();
+    for (entity, (path, transform)) in paths.iter_mut().zip(transforms.iter_mut()).enumerate() {
+        let (Some(path), Some(transform)) = (path, transform) else {
+            continue;
+        };
+        if !path.playing {
+            continue;
+        }
+        path.start_transition(transform);
+
+        path.elapsed += time.0 as f32;
+        let duration = path.duration();
+        if path.elapsed >= duration {
+            if path.looping && duration > 0.0 {
+                path.elapsed %= duration;
+            } else {
+                path.elapsed = duration;
+                path.playing = false;
+            }
+        }
+
+        let Some((position, rotation, fov)) = path.sample(path.elapsed) else {
+            continue;
+        };
+        transform.translation = position;
+        transform.rotation = rotation;
+        let projection = projections
+            .as_mut()
+            .and_then(|projections| projections.get_mut(entity)?.as_mut());
+        if let (Some(fov), Some(Projection::Perspective(camera))) = (fov, projection) {
+            camera.update_fov(fov);
+        }
+    }
+}
+
+#[test]
+fn test_camera_path() {
+    let path = CameraPath::new(vec![
+        CameraKeyframe::new(0.0, Vec3::ZERO, Quat::IDENTITY).with_fov(60.0),
+        CameraKeyframe::new(1.0, Vec3::X, Quat::from_rotation_y(1.0)).with_fov(90.0),
+        CameraKeyframe::new(2.0, Vec3::new(1.0, 1.0, 0.0), Quat::IDENTITY),
+    ]);
+    for interpolation in [
+        PathInterpolation::Linear,
+        PathInterpolation::CatmullRom,
+        PathInterpolation::Bezier,
+    ] {
+        let path = path.clone().with_interpolation(interpolation);
+        // Every interpolation passes through the keyframes
+        let (position, rotation, fov) = path.sample(1.0).unwrap();
+        assert!(position.abs_diff_eq(Vec3::X, 1e-5));
+        assert!(rotation.abs_diff_eq(Quat::from_rotation_y(1.0), 1e-5));
+        assert_eq!(fov, Some(90.0));
+        let (position, _, fov) = path.sample(0.5).unwrap();
+        assert!(position.x > 0.0 && position.x < 1.0);
+        assert_eq!(fov, Some(75.0));
+    }
+    assert!(path
+        .sample(5.0)
+        .unwrap()
+        .0
+        .abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), 1e-5));
+
+    let mut transition = CameraPath::look_at(Vec3::new(0.0, 0.0, 1.0), 1.0);
+    transition.start_transition(&CameraTransform::IDENTITY);
+    let (position, rotation, _) = transition.sample(1.0).unwrap();
+    assert_eq!(position, Vec3::ZERO);
+    assert!((rotation * Vec3::NEG_Z).abs_diff_eq(Vec3::Z, 1e-5));
+
+    // Playing a transition again starts over from wherever the camera is by then
+    let moved = CameraTransform {
+        translation: Vec3::Y,
+        ..CameraTransform::IDENTITY
+    };
+    transition.start_transition(&moved);
+    assert_eq!(transition.keyframes()[0].position, Vec3::ZERO);
+    transition.play();
+    transition.start_transition(&moved);
+    assert_eq!(transition.keyframes()[0].position, Vec3::Y);
+    assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_camera_path_without_projection() {
+    // e.g. a camera rig parent
+    let mut world = World::new();
+    world.add_resource(Time(0.5));
+    let rig = world.new_entity();
+    world.add_component_to_entity(rig, CameraTransform::IDENTITY);
+    world.add_component_to_entity(
+        rig,
+        CameraPath::new(vec![
+            CameraKeyframe::new(0.0, Vec3::ZERO, Quat::IDENTITY).with_fov(60.0),
+            CameraKeyframe::new(1.0, Vec3::X, Quat::IDENTITY).with_fov(90.0),
+        ])
+        .with_interpolation(PathInterpolation::Linear),
+    );
+    update_camera_path_system(&mut world);
+
+    let transforms = world.borrow_component_vec::<CameraTransform>().unwrap();
+    let translation = transforms[rig].as_ref().unwrap().translation;
+    assert!(translation.abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-5));
+}
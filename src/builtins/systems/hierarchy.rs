@@ -0,0 +1,101 @@
+use crate::{ecs::World, graphics::Transform};
+
+/// Component making an entity the child of another, so that its [`Transform`] follows the
+/// parent's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parent(pub usize);
+
+/// Component with the transform of a child entity relative to its [`Parent`].
+///
+/// The [`Transform`] of the entity is derived from it by [`propagate_transforms_system`], so
+/// move children by changing this component rather than their `Transform`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LocalTransform(pub Transform);
+
+/// Sets the [`Transform`] of every entity with a [`Parent`] and a [`LocalTransform`] to the
+/// parent's `Transform` combined with the local one, parents before their children.
+///
+/// Entities without a parent keep their `Transform`, so moving the root of a hierarchy moves
+/// everything below it. A parent that doesn't exist or a cycle of parents ends the chain.
+pub fn propagate_transforms_system(world: &mut World) {
+    let (Some(parents), Some(locals), Some(mut transforms)) = (
+        world.borrow_component_vec::<Parent>(),
+        world.borrow_component_vec::<LocalTransform>(),
+        world.borrow_component_vec_mut::<Transform>(),
+    ) else {
+        return;
+    };
+    let link = |entity: usize| {
+        let Parent(parent) = parents.get(entity)?.as_ref()?;
+        let LocalTransform(local) = locals.get(entity)?.as_ref()?;
+        Some((*parent, *local))
+    };
+
+    let mut done = vec![false; world.num_entities];
+    for entity in 0..world.num_entities {
+        // Walk up to the first ancestor that is up to date or a root, then update back down
+        let mut chain = Vec::new();
+        let mut current = entity;
+        while !done[current] && !chain.contains(&current) {
+            let Some((parent, _)) = link(current) else {
+                break;
+            };
+            chain.push(current);
+            if parent >= world.num_entities {
+                break;
+            }
+            current = parent;
+        }
+        for &child in chain.iter().rev() {
+            let (parent, local) = link(child).unwrap();
+            let parent_transform = transforms.get(parent).copied().flatten();
+            if let (Some(parent_transform), Some(Some(transform))) =
+                (parent_transform, transforms.get_mut(child))
+            {
+                *transform =
+                    Transform::from(parent_transform.compute_matrix() * local.compute_matrix());
+            }
+            done[child] = true;
+        }
+        done[entity] = true;
+    }
+}
+
+#[test]
+fn test_propagate_transforms() {
+    use glam::Vec3;
+
+    let mut world = World::new();
+    // Children spawned before their parents are still updated after them
+    let grandchild = world.new_entity();
+    let child = world.new_entity();
+    let root = world.new_entity();
+    world.add_component_to_entity(
+        root,
+        Transform::from_xyz(1.0, 0.0, 0.0).with_scale(Vec3::splat(2.0)),
+    );
+    for (entity, parent) in [(child, root), (grandchild, child)] {
+        world.add_component_to_entity(entity, Transform::IDENTITY);
+        world.add_component_to_entity(entity, Parent(parent));
+        world.add_component_to_entity(entity, LocalTransform(Transform::from_xyz(0.0, 1.0, 0.0)));
+    }
+    propagate_transforms_system(&mut world);
+
+    let translation = |world: &World, entity: usize| {
+        world.borrow_component_vec::<Transform>().unwrap()[entity]
+            .unwrap()
+            .translation
+    };
+    assert!(translation(&world, child).abs_diff_eq(Vec3::new(1.0, 2.0, 0.0), 1e-5));
+    assert!(translation(&world, grandchild).abs_diff_eq(Vec3::new(1.0, 4.0, 0.0), 1e-5));
+
+    // Moving the root moves its descendants
+    world.add_component_to_entity(root, Transform::from_xyz(5.0, 0.0, 0.0));
+    propagate_transforms_system(&mut world);
+    assert!(translation(&world, grandchild).abs_diff_eq(Vec3::new(5.0, 2.0, 0.0), 1e-5));
+
+    // A cycle doesn't hang
+    world.add_component_to_entity(root, Parent(grandchild));
+    world.add_component_to_entity(root, LocalTransform::default());
+    propagate_transforms_system(&mut world);
+}
@@ -1,53 +1,184 @@
 use std::sync::Arc;
 
+use glam::Vec2;
+use thiserror::Error;
 use tokio::runtime::Runtime;
 use wgpu::util::DeviceExt;
 use winit::window::Window;
 
 use crate::ecs::query::Query;
-use crate::ecs::resource::ResMut;
+use crate::ecs::resource::{Res, ResMut};
 use crate::ecs::World;
-use crate::graphics::camera::{CameraTransform, CameraUniform};
+use crate::graphics::camera::{CameraTransform, CameraUniform, RenderTarget};
 use crate::graphics::material::LambertMaterial;
-use crate::graphics::mesh::MeshVertex;
-use crate::graphics::transform::Mat4Uniform;
-use crate::graphics::{Mesh, PerspectiveCamera, Transform};
+use crate::graphics::mesh::{MeshAttribute, MeshError};
+use crate::graphics::transform::ModelUniform;
+use crate::graphics::{Camera, Mesh, Projection, Transform};
 use crate::render::render_pass::RenderPassBuilder;
 use crate::render::texture;
-use crate::render::vertex::Vertex;
+use crate::render::vertex::{VertexLayout, VertexLayoutError};
+use crate::render::DepthMode;
 use crate::render::Renderer;
 
-use super::ActiveCamera;
+use super::visibility::Visibility;
+use super::NoActiveCamera;
+
+#[derive(Debug, Error)]
+pub enum RenderError {
+    #[error(transparent)]
+    NoActiveCamera(#[from] NoActiveCamera),
+    #[error("Missing resource: {0}")]
+    MissingResource(&'static str),
+    #[error("Missing render pipeline: {0}")]
+    MissingPipeline(&'static str),
+    #[error("Missing vertex layout: {0}")]
+    MissingVertexLayout(&'static str),
+    #[error("Surface error: {0}")]
+    Surface(#[from] wgpu::SurfaceError),
+    #[error("Invalid vertex layout: {0}")]
+    VertexLayout(#[from] VertexLayoutError),
+    #[error("Runtime error: {0}")]
+    Runtime(#[from] std::io::Error),
+}
 
-pub fn init_renderer_system(world: &mut World) {
-    let window = world.get_resource::<Arc<Window>>().unwrap().clone();
-    let rt = Runtime::new().unwrap();
-    let renderer = rt.block_on(async { Renderer::new(window).await });
+pub fn init_renderer_system(world: &mut World) -> Result<(), RenderError> {
+    let window = world
+        .get_resource::<Arc<Window>>()
+        .ok_or(RenderError::MissingResource("Arc<Window>"))?
+        .clone();
+    let depth_mode = world
+        .get_resource::<DepthMode>()
+        .map(|depth_mode| *depth_mode)
+        .unwrap_or_default();
+    let rt = Runtime::new()?;
+    let mut renderer = rt.block_on(async { Renderer::new(window).await });
+    renderer.depth_mode = depth_mode;
     world.add_resource(renderer);
+    Ok(())
 }
 
-pub fn update_render_system(
-    renderer: ResMut<Renderer>,
-    models: Query<(&Mesh, &Transform, &LambertMaterial)>,
-    camera: Query<(&PerspectiveCamera, &CameraTransform, &ActiveCamera)>,
+/// Keeps the projection of every [`Camera`] matched to the size of its viewport, which follows
+/// the window size, and to the [`DepthMode`] of the [`Renderer`].
+///
+/// Runs before culling and rendering, so that both use the projection of the current frame.
+pub fn update_camera_projection_system(
+    renderer: Res<Renderer>,
+    mut cameras: Query<(&mut Projection, &Camera)>,
 ) {
-    let (camera, camera_transform, _) = camera.into_iter().next().expect("No active camera found");
+    for (projection, camera) in &mut cameras {
+        let (_, viewport_size) = camera
+            .viewport
+            .to_pixels(target_size(&renderer, &camera.target));
+        projection.update_viewport_size(viewport_size.x, viewport_size.y);
+        if projection.get_depth_mode() != renderer.depth_mode {
+            projection.update_depth_mode(renderer.depth_mode);
+        }
+    }
+}
 
-    let device = &renderer.context.device;
+/// The size of a camera's render target in pixels.
+fn target_size(renderer: &Renderer, target: &RenderTarget) -> Vec2 {
+    match target {
+        RenderTarget::Window => Vec2::new(
+            renderer.context.config.width as f32,
+            renderer.context.config.height as f32,
+        ),
+        RenderTarget::Texture(texture) => Vec2::new(
+            texture.texture.width() as f32,
+            texture.texture.height() as f32,
+        ),
+    }
+}
 
-    let camera_uniform = CameraUniform::from_inv_view_proj(
-        &Transform::from(camera_transform).into(),
-        &camera.get_projection_matrix(),
-    );
-    let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some("Camera Buffer"),
-        contents: bytemuck::cast_slice(&[camera_uniform]),
-        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+/// Component caching whether the [`Mesh`] of an entity can be drawn, added by
+/// [`check_mesh_system`] the first time it sees the entity.
+///
+/// The mesh is not checked again while the component is there, so set it back to
+/// [`MeshCheck::Pending`] after replacing or editing the mesh.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum MeshCheck {
+    #[default]
+    Pending,
+    Drawable,
+    /// The entity is skipped by [`update_render_system`].
+    Invalid(MeshError),
+}
+
+/// Checks every [`Mesh`] that has no [`MeshCheck`] yet or is pending, reporting each entity whose
+/// mesh cannot be drawn with the scene pipeline once and marking it to be skipped instead of
+/// failing the frame.
+pub fn check_mesh_system(world: &mut World) -> Result<(), RenderError> {
+    let layout = world
+        .get_resource::<Renderer>()
+        .ok_or(RenderError::MissingResource("Renderer"))?
+        .vertex_layouts
+        .get("scene")
+        .ok_or(RenderError::MissingVertexLayout("scene"))?
+        .clone();
+    check_meshes(world, &layout);
+    Ok(())
+}
+
+fn check_meshes(world: &mut World, layout: &VertexLayout) {
+    let checks = {
+        let Some(meshes) = world.borrow_component_vec::<Mesh>() else {
+            return;
+        };
+        let checks = world.borrow_component_vec::<MeshCheck>();
+        meshes
+            .iter()
+            .enumerate()
+            .filter_map(|(entity, mesh)| {
+                let check = checks
+                    .as_ref()
+                    .and_then(|checks| checks.get(entity)?.as_ref());
+                if !matches!(check, None | Some(MeshCheck::Pending)) {
+                    return None;
+                }
+                let mesh = mesh.as_ref()?;
+                let check = match mesh
+                    .validate_drawable()
+                    .and_then(|()| mesh.validate_layout(layout))
+                {
+                    Ok(()) => MeshCheck::Drawable,
+                    Err(error) => {
+                        eprintln!("Not drawing entity {}: {}", entity, error);
+                        MeshCheck::Invalid(error)
+                    }
+                };
+                Some((entity, check))
+            })
+            .collect::<Vec<_>>()
+    };
+    for (entity, check) in checks {
+        world.add_component_to_entity(entity, check);
+    }
+}
+
+/// The components of an entity drawn by [`update_render_system`].
+type Model<'a> = (
+    &'a Mesh,
+    &'a Transform,
+    &'a LambertMaterial,
+    Option<&'a Visibility>,
+    Option<&'a MeshCheck>,
+);
+
+pub fn update_render_system(
+    renderer: ResMut<Renderer>,
+    models: Query<Model<'_>>,
+    cameras: Query<(&Projection, &CameraTransform, &Camera)>,
+) -> Result<(), RenderError> {
+    let mut cameras = (&cameras).into_iter().collect::<Vec<_>>();
+    if cameras.is_empty() {
+        return Err(NoActiveCamera.into());
+    }
+    // Offscreen targets are drawn first so that window cameras can show them
+    cameras.sort_by_key(|(_, _, camera)| {
+        (matches!(camera.target, RenderTarget::Window), camera.order)
     });
-    renderer
-        .context
-        .queue
-        .write_buffer(&camera_buffer, 0, bytemuck::cast_slice(&[camera_uniform]));
+
+    let device = &renderer.context.device;
 
     let camera_bind_group_layout =
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -63,14 +194,28 @@ pub fn update_render_system(
             }],
             label: Some("camera_bind_group_layout"),
         });
-    let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-        layout: &camera_bind_group_layout,
-        entries: &[wgpu::BindGroupEntry {
-            binding: 0,
-            resource: camera_buffer.as_entire_binding(),
-        }],
-        label: Some("camera_bind_group"),
-    });
+    let camera_bind_groups = cameras
+        .iter()
+        .map(|(projection, camera_transform, _)| {
+            let camera_uniform = CameraUniform::from_inv_view_proj(
+                &camera_transform.compute_matrix(),
+                &projection.get_projection_matrix(),
+            );
+            let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Camera Buffer"),
+                contents: bytemuck::cast_slice(&[camera_uniform]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &camera_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                }],
+                label: Some("camera_bind_group"),
+            })
+        })
+        .collect::<Vec<_>>();
 
     let model_bind_group_layout =
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -87,35 +232,83 @@ pub fn update_render_system(
             label: Some("model_bind_group_layout"),
         });
 
-    let scene_render_pipeline = renderer.pipelines.get("scene").unwrap();
-    let crosshair_render_pipeline = renderer.pipelines.get("crosshair").unwrap();
+    let scene_render_pipeline = renderer
+        .pipelines
+        .get("scene")
+        .ok_or(RenderError::MissingPipeline("scene"))?;
+    let scene_vertex_layout = renderer
+        .vertex_layouts
+        .get("scene")
+        .ok_or(RenderError::MissingVertexLayout("scene"))?;
+    let clear_render_pipeline = renderer
+        .pipelines
+        .get("clear")
+        .ok_or(RenderError::MissingPipeline("clear"))?;
+    let crosshair_render_pipeline = renderer
+        .pipelines
+        .get("crosshair")
+        .ok_or(RenderError::MissingPipeline("crosshair"))?;
 
     let mut encoder = renderer.create_command_encoder(None);
-    let render_pass_builder = RenderPassBuilder::new();
-    let surface_texture = renderer.context.surface.get_current_texture().unwrap();
+    let surface_texture = match renderer.context.surface.get_current_texture() {
+        Ok(surface_texture) => surface_texture,
+        Err(e) => {
+            // Reconfigure a lost or outdated surface so that the next frame can be drawn
+            if matches!(e, wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) {
+                let context = &renderer.context;
+                context.surface.configure(&context.device, &context.config);
+            }
+            return Err(e.into());
+        }
+    };
     let view = Renderer::get_current_texture_view(&surface_texture);
     let depth_texture = texture::Texture::create_depth_texture(
         &renderer.context.device,
         &renderer.context.config,
+        renderer.depth_mode,
         "depth_texture",
     );
+    let offscreen_depth_textures = cameras
+        .iter()
+        .map(|(_, _, camera)| match &camera.target {
+            RenderTarget::Window => None,
+            RenderTarget::Texture(target) => {
+                Some(texture::Texture::create_depth_texture_with_size(
+                    device,
+                    target.texture.width(),
+                    target.texture.height(),
+                    renderer.depth_mode,
+                    "offscreen_depth_texture",
+                ))
+            }
+        })
+        .collect::<Vec<_>>();
 
     let mut meshes = Vec::new();
+    let mut vertex_data = Vec::new();
     let mut transforms = Vec::new();
     let mut materials = Vec::new();
 
-    for (mesh, transform, material) in &models {
+    for (mesh, transform, material, visibility, check) in &models {
+        if visibility == Some(&Visibility::Culled) || matches!(check, Some(MeshCheck::Invalid(_))) {
+            continue;
+        }
+        // Meshes that fail here are reported by `check_mesh_system`
+        let Ok(vertices) = mesh.vertex_buffer_data(scene_vertex_layout) else {
+            continue;
+        };
+        vertex_data.push(vertices);
         meshes.push(mesh);
         transforms.push(transform);
         materials.push(material);
     }
 
-    let vertex_buffers = meshes
+    let vertex_buffers = vertex_data
         .iter()
-        .map(|mesh| {
+        .map(|vertices| {
             device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Vertex Buffer"),
-                contents: bytemuck::cast_slice(&mesh.vertices),
+                contents: vertices,
                 usage: wgpu::BufferUsages::VERTEX,
             })
         })
@@ -126,7 +319,7 @@ pub fn update_render_system(
         .map(|transform| {
             device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Model Buffer"),
-                contents: bytemuck::cast_slice(&[Mat4Uniform::from(*transform)]),
+                contents: bytemuck::cast_slice(&[ModelUniform::from(*transform)]),
                 usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             })
         })
@@ -243,14 +436,48 @@ pub fn update_render_system(
         .map(|mesh| mesh.indices.len() as u32)
         .collect::<Vec<_>>();
 
+    // Each target is cleared by the first camera that draws to it; later cameras draw on top
+    let mut cleared_targets = Vec::new();
+    let mut color_load = |target: &RenderTarget| {
+        let target = match target {
+            RenderTarget::Window => None,
+            RenderTarget::Texture(texture) => Some(Arc::as_ptr(texture)),
+        };
+        if cleared_targets.contains(&target) {
+            wgpu::LoadOp::Load
+        } else {
+            cleared_targets.push(target);
+            wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+        }
+    };
+
+    for (((_, _, camera), camera_bind_group), offscreen_depth_texture) in cameras
+        .iter()
+        .zip(camera_bind_groups.iter())
+        .zip(offscreen_depth_textures.iter())
     {
-        let mut render_pass = render_pass_builder
-            .color_attachment(&view)
-            .depth_stencil_attachment(&depth_texture.view)
+        let (color_view, depth_view) = match (&camera.target, offscreen_depth_texture) {
+            (RenderTarget::Texture(target), Some(depth)) => (&target.view, &depth.view),
+            _ => (&view, &depth_texture.view),
+        };
+        let mut render_pass = RenderPassBuilder::new()
+            .depth_mode(renderer.depth_mode)
+            .color_attachment_with_load(color_view, color_load(&camera.target))
+            .depth_stencil_attachment(depth_view)
             .begin_render_pass(&mut encoder, None);
 
+        let (position, size) = camera
+            .viewport
+            .to_pixels(target_size(&renderer, &camera.target));
+        render_pass.set_viewport(position.x, position.y, size.x, size.y, 0.0, 1.0);
+        if let Some(clear_color) = camera.clear_color {
+            render_pass.set_pipeline(clear_render_pipeline);
+            render_pass.set_blend_constant(clear_color);
+            render_pass.draw(0..3, 0..1);
+        }
+
         render_pass.set_pipeline(scene_render_pipeline);
-        render_pass.set_bind_group(0, &camera_bind_group, &[]);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
         for (
             (((vertex_buffer, index_buffer), num_indices), model_bind_group),
             material_bind_group,
@@ -267,6 +494,14 @@ pub fn update_render_system(
             render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
             render_pass.draw_indexed(0..*num_indices, 0, 0..1);
         }
+    }
+
+    {
+        let mut render_pass = RenderPassBuilder::new()
+            .depth_mode(renderer.depth_mode)
+            .color_attachment_with_load(&view, color_load(&RenderTarget::Window))
+            .depth_stencil_attachment(&depth_texture.view)
+            .begin_render_pass(&mut encoder, None);
 
         render_pass.set_pipeline(crosshair_render_pipeline);
         render_pass.set_bind_group(0, &settings_bind_group, &[]);
@@ -275,16 +510,21 @@ pub fn update_render_system(
 
     renderer.context.queue.submit([encoder.finish()]);
     surface_texture.present();
+    Ok(())
 }
 
-pub fn init_pipeline_system(world: &mut World) {
-    let mut renderer = world.get_resource_mut::<Renderer>().unwrap();
+pub fn init_pipeline_system(world: &mut World) -> Result<(), RenderError> {
+    let mut renderer = world
+        .get_resource_mut::<Renderer>()
+        .ok_or(RenderError::MissingResource("Renderer"))?;
     let device = &renderer.context.device;
 
+    let shader_source = include_str!("../shaders/scene.wgsl");
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/scene.wgsl").into()),
+        source: wgpu::ShaderSource::Wgsl(shader_source.into()),
     });
+    let vertex_layout = VertexLayout::from_wgsl(shader_source, "vs_main", &MeshAttribute::BUILTIN)?;
 
     let camera_bind_group_layout =
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -347,7 +587,7 @@ pub fn init_pipeline_system(world: &mut World) {
         vertex: wgpu::VertexState {
             module: &shader,
             entry_point: "vs_main",
-            buffers: &[MeshVertex::desc()],
+            buffers: &[vertex_layout.buffer_layout()],
             compilation_options: Default::default(),
         },
         fragment: Some(wgpu::FragmentState {
@@ -376,7 +616,7 @@ pub fn init_pipeline_system(world: &mut World) {
         depth_stencil: Some(wgpu::DepthStencilState {
             format: wgpu::TextureFormat::Depth32Float,
             depth_write_enabled: true,
-            depth_compare: wgpu::CompareFunction::Less,
+            depth_compare: renderer.depth_mode.compare(),
             stencil: wgpu::StencilState::default(),
             bias: wgpu::DepthBiasState::default(),
         }),
@@ -394,10 +634,95 @@ pub fn init_pipeline_system(world: &mut World) {
     renderer
         .pipelines
         .insert("scene".to_string(), render_pipeline);
+    renderer
+        .vertex_layouts
+        .insert("scene".to_string(), vertex_layout);
+    let clear_pipeline = create_clear_pipeline(&renderer);
+    renderer
+        .pipelines
+        .insert("clear".to_string(), clear_pipeline);
+    Ok(())
 }
 
-pub fn init_crosshair_pipeline_system(world: &mut World) {
-    let mut renderer = world.get_resource_mut::<Renderer>().unwrap();
+/// The blending of the clear pipeline, which replaces the target with the fragment output, which
+/// is one, times the blend constant.
+const CLEAR_BLEND: wgpu::BlendState = {
+    let replace_with_constant = wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::Constant,
+        dst_factor: wgpu::BlendFactor::Zero,
+        operation: wgpu::BlendOperation::Add,
+    };
+    wgpu::BlendState {
+        color: replace_with_constant,
+        alpha: replace_with_constant,
+    }
+};
+
+/// Creates the pipeline that clears a camera's viewport to the blend constant, since a render
+/// pass can only clear its whole attachment.
+fn create_clear_pipeline(renderer: &Renderer) -> wgpu::RenderPipeline {
+    let device = &renderer.context.device;
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Clear Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/clear.wgsl").into()),
+    });
+
+    let clear_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Clear Pipeline Layout"),
+        ..Default::default()
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Clear Pipeline"),
+        layout: Some(&clear_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: renderer.context.config.format,
+                blend: Some(CLEAR_BLEND),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: Some(wgpu::Face::Back),
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        // The depth attachment is cleared by the render pass, so it is left untouched here
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
+
+pub fn init_crosshair_pipeline_system(world: &mut World) -> Result<(), RenderError> {
+    let mut renderer = world
+        .get_resource_mut::<Renderer>()
+        .ok_or(RenderError::MissingResource("Renderer"))?;
     let device = &renderer.context.device;
 
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -460,7 +785,7 @@ pub fn init_crosshair_pipeline_system(world: &mut World) {
         depth_stencil: Some(wgpu::DepthStencilState {
             format: wgpu::TextureFormat::Depth32Float,
             depth_write_enabled: true,
-            depth_compare: wgpu::CompareFunction::Less,
+            depth_compare: renderer.depth_mode.compare(),
             stencil: wgpu::StencilState::default(),
             bias: wgpu::DepthBiasState::default(),
         }),
@@ -476,6 +801,7 @@ pub fn init_crosshair_pipeline_system(world: &mut World) {
     renderer
         .pipelines
         .insert("crosshair".to_string(), render_pipeline);
+    Ok(())
 }
 
 #[repr(C)]
@@ -487,3 +813,107 @@ struct CrosshairSettings {
     pub gap: u32,
     pub padding: u32,
 }
+
+#[test]
+fn test_clear_blend() {
+    use wgpu::naga;
+
+    // The pipeline outputs `src * constant + dst * 0` for every channel
+    let replace_with_constant = wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::Constant,
+        dst_factor: wgpu::BlendFactor::Zero,
+        operation: wgpu::BlendOperation::Add,
+    };
+    assert_eq!(
+        CLEAR_BLEND,
+        wgpu::BlendState {
+            color: replace_with_constant,
+            alpha: replace_with_constant,
+        }
+    );
+
+    // so the fragment shader has to return one for the result to be the clear colour
+    let module = naga::front::wgsl::parse_str(include_str!("../shaders/clear.wgsl")).unwrap();
+    let function = &module
+        .entry_points
+        .iter()
+        .find(|entry| entry.name == "fs_main")
+        .unwrap()
+        .function;
+    let returned = function
+        .body
+        .iter()
+        .find_map(|statement| match statement {
+            naga::Statement::Return { value } => *value,
+            _ => None,
+        })
+        .unwrap();
+    let is_one = |expression| {
+        matches!(
+            function.expressions[expression],
+            naga::Expression::Literal(naga::Literal::F32(1.0))
+        )
+    };
+    match &function.expressions[returned] {
+        naga::Expression::Splat { value, .. } => assert!(is_one(*value)),
+        naga::Expression::Compose { components, .. } => {
+            assert!(components.iter().copied().all(is_one))
+        }
+        expression => panic!("unexpected fragment output {:?}", expression),
+    }
+}
+
+#[test]
+fn test_check_mesh() {
+    use crate::builtins::primitives::Cuboid;
+
+    let mut world = World::new();
+    let [valid, invalid] = [0, 1].map(|_| {
+        let entity = world.new_entity();
+        world.add_component_to_entity(entity, Mesh::from(Cuboid::default()));
+        entity
+    });
+    let mut broken = Mesh::from(Cuboid::default());
+    broken.indices.push(0);
+    world.add_component_to_entity(invalid, broken);
+    let layout = VertexLayout::from_wgsl(
+        include_str!("../shaders/scene.wgsl"),
+        "vs_main",
+        &MeshAttribute::BUILTIN,
+    )
+    .unwrap();
+    check_meshes(&mut world, &layout);
+
+    let checks = |world: &World| {
+        let checks = world.borrow_component_vec::<MeshCheck>().unwrap();
+        [valid, invalid].map(|entity| checks[entity].clone())
+    };
+    assert_eq!(
+        checks(&world),
+        [
+            Some(MeshCheck::Drawable),
+            Some(MeshCheck::Invalid(MeshError::IncompleteTriangle(37)))
+        ]
+    );
+
+    // Fixing the mesh is only noticed once the check is reset
+    world.add_component_to_entity(invalid, Mesh::from(Cuboid::default()));
+    check_meshes(&mut world, &layout);
+    assert!(matches!(checks(&world)[1], Some(MeshCheck::Invalid(_))));
+    world.add_component_to_entity(invalid, MeshCheck::Pending);
+    check_meshes(&mut world, &layout);
+    assert_eq!(checks(&world)[1], Some(MeshCheck::Drawable));
+
+    // So are meshes without every attribute the scene pipeline reads
+    let mut untextured = Mesh::from(Cuboid::default());
+    untextured.remove_attribute(MeshAttribute::TEX_COORDS);
+    world.add_component_to_entity(valid, untextured);
+    world.add_component_to_entity(valid, MeshCheck::Pending);
+    check_meshes(&mut world, &layout);
+    assert_eq!(
+        checks(&world)[0],
+        Some(MeshCheck::Invalid(MeshError::MissingAttribute(
+            "tex_coords"
+        )))
+    );
+}
@@ -0,0 +1,157 @@
+use crate::{
+    ecs::World,
+    graphics::{
+        bounds::Aabb, camera::CameraTransform, frustum::Frustum, Camera, Mesh, Projection,
+        Transform,
+    },
+};
+
+/// Component set by [`update_visibility_system`] on every entity with a [`Mesh`] and a
+/// [`Transform`], telling the renderer whether to draw it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Visibility {
+    /// Inside the frustum of at least one camera.
+    #[default]
+    Visible,
+    /// Outside the frustum of every camera.
+    Culled,
+}
+
+/// Resource with the results of the last run of [`update_visibility_system`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CullingStats {
+    pub drawn: usize,
+    pub culled: usize,
+}
+
+/// Marks every mesh entity [`Visible`](Visibility::Visible) or [`Culled`](Visibility::Culled)
+/// by testing its bounds against the frustum of every entity with a [`Camera`].
+///
+/// Meshes without an [`Aabb`] get one computed from their vertices, which is kept until it is
+/// replaced, so after changing the vertices of a mesh, insert its new
+/// [bounds](Mesh::compute_aabb) as well. Empty meshes are always culled, and everything is culled
+/// when there is no camera.
+///
+/// The projections should be up to date with the viewports, see
+/// [`update_camera_projection_system`](super::render::update_camera_projection_system).
+pub fn update_visibility_system(world: &mut World) {
+    let frusta = match (
+        world.borrow_component_vec::<Camera>(),
+        world.borrow_component_vec::<Projection>(),
+        world.borrow_component_vec::<CameraTransform>(),
+    ) {
+        // Other entities with a projection, e.g. cameras imported from glTF, don't render
+        (Some(cameras), Some(projections), Some(camera_transforms)) => (0..world.num_entities)
+            .filter_map(|entity| {
+                cameras.get(entity)?.as_ref()?;
+                Some(Frustum::from_camera(
+                    projections.get(entity)?.as_ref()?,
+                    camera_transforms.get(entity)?.as_ref()?,
+                ))
+            })
+            .collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+
+    let missing_bounds = {
+        let Some(meshes) = world.borrow_component_vec::<Mesh>() else {
+            return;
+        };
+        let bounds = world.borrow_component_vec::<Aabb>();
+        meshes
+            .iter()
+            .enumerate()
+            .filter(|(entity, mesh)| {
+                mesh.is_some()
+                    && bounds
+                        .as_ref()
+                        .and_then(|bounds| bounds.get(*entity)?.as_ref())
+                        .is_none()
+            })
+            .filter_map(|(entity, mesh)| Some((entity, mesh.as_ref()?.compute_aabb()?)))
+            .collect::<Vec<_>>()
+    };
+    // Cached until replaced: nothing tracks changes to the mesh
+    for (entity, aabb) in missing_bounds {
+        world.add_component_to_entity(entity, aabb);
+    }
+
+    let visibilities = {
+        let (Some(meshes), Some(transforms)) = (
+            world.borrow_component_vec::<Mesh>(),
+            world.borrow_component_vec::<Transform>(),
+        ) else {
+            return;
+        };
+        let bounds = world.borrow_component_vec::<Aabb>();
+        (0..world.num_entities)
+            .filter_map(|entity| {
+                meshes.get(entity)?.as_ref()?;
+                let transform = transforms.get(entity)?.as_ref()?;
+                let aabb = bounds
+                    .as_ref()
+                    .and_then(|bounds| bounds.get(entity)?.as_ref())
+                    .map(|aabb| aabb.transformed(&transform.compute_matrix()));
+                let visible = aabb.is_some_and(|aabb| {
+                    frusta.iter().any(|frustum| frustum.intersects_aabb(&aabb))
+                });
+                let visibility = if visible {
+                    Visibility::Visible
+                } else {
+                    Visibility::Culled
+                };
+                Some((entity, visibility))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut stats = CullingStats::default();
+    for (entity, visibility) in visibilities {
+        match visibility {
+            Visibility::Visible => stats.drawn += 1,
+            Visibility::Culled => stats.culled += 1,
+        }
+        world.add_component_to_entity(entity, visibility);
+    }
+    world.add_resource(stats);
+}
+
+#[test]
+fn test_update_visibility() {
+    use crate::builtins::primitives::Cuboid;
+    use crate::graphics::PerspectiveCamera;
+    use glam::Vec3;
+
+    let mut world = World::new();
+    let projection = Projection::from(PerspectiveCamera::new(90.0, 1.0, 0.1, 100.0));
+    let camera = world.new_entity();
+    world.add_component_to_entity(camera, Camera::new());
+    world.add_component_to_entity(camera, projection.clone());
+    world.add_component_to_entity(camera, CameraTransform::IDENTITY);
+    // A projection without a camera, looking the other way, must not make anything visible
+    let imported = world.new_entity();
+    world.add_component_to_entity(imported, projection);
+    world.add_component_to_entity(
+        imported,
+        CameraTransform::IDENTITY.looking_at(Vec3::Z, Vec3::Y),
+    );
+
+    let [ahead, behind] = [-5.0, 5.0].map(|z| {
+        let entity = world.new_entity();
+        world.add_component_to_entity(entity, Mesh::from(Cuboid::default()));
+        world.add_component_to_entity(entity, Transform::from_xyz(0.0, 0.0, z));
+        entity
+    });
+    update_visibility_system(&mut world);
+
+    let visibilities = world.borrow_component_vec::<Visibility>().unwrap();
+    assert_eq!(visibilities[ahead], Some(Visibility::Visible));
+    assert_eq!(visibilities[behind], Some(Visibility::Culled));
+    assert_eq!(
+        *world.get_resource::<CullingStats>().unwrap(),
+        CullingStats {
+            drawn: 1,
+            culled: 1
+        }
+    );
+}
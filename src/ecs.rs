@@ -1,5 +1,6 @@
 mod component;
 pub mod query;
+pub mod state;
 pub mod system;
 pub mod world;
 
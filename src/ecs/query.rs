@@ -4,6 +4,7 @@ use super::{system::SystemParam, World};
 
 /// Want to write systems as functions with parameters like this:
 /// ```rust
+/// # use carnot::prelude::*;
 /// fn system(query: Query<&Transform>) {
 ///     for transform in &query {
 ///        // transform is a &Transform
@@ -13,13 +14,15 @@ use super::{system::SystemParam, World};
 /// ```
 /// or, if you need mutable access to the components:
 /// ```rust
-/// fn system(query: Query<(&Mesh, &mut Transform)>) {
+/// # use carnot::prelude::*;
+/// fn system(mut query: Query<(&Mesh, &mut Transform)>) {
 ///     for (mesh, transform) in &mut query {
 ///         // mesh is a &Mesh
 ///         // transform is a &mut Transform
 ///         // do something with mesh and transform
 ///     }
 /// }
+/// ```
 pub struct Query<'a, D: QueryData> {
     refs: D::ItemVecRefs<'a>,
 }
@@ -108,6 +111,29 @@ impl<T: 'static> QueryData for &mut T {
     }
 }
 
+/// Matches every entity, yielding the component if the entity has one.
+impl<T: 'static> QueryData for Option<&T> {
+    type Item<'a> = Option<&'a T>;
+    type ItemVecRefs<'a> = (Option<Ref<'a, Vec<Option<T>>>>, usize);
+
+    fn fetch<'a, 'w: 'a>(world: &'w World) -> Self::ItemVecRefs<'a> {
+        (world.borrow_component_vec::<T>(), world.num_entities)
+    }
+
+    fn refs_to_sparse_iter<'a>(
+        refs: &'a Self::ItemVecRefs<'_>,
+    ) -> impl Iterator<Item = Option<Self::Item<'a>>> {
+        let (components, num_entities) = refs;
+        (0..*num_entities).map(move |entity| {
+            Some(
+                components
+                    .as_ref()
+                    .and_then(|components| components.get(entity)?.as_ref()),
+            )
+        })
+    }
+}
+
 impl<D: QueryData> QueryData for (D,) {
     type Item<'a> = (D::Item<'a>,);
     type ItemVecRefs<'a> = (D::ItemVecRefs<'a>,);
@@ -302,3 +328,27 @@ impl<D1: QueryData, D2: QueryData, D3: QueryData, D4: QueryData, D5: QueryData>
         )
     }
 }
+
+#[test]
+fn test_optional_query() {
+    struct A(u32);
+    struct B;
+
+    let mut world = World::new();
+    let first = world.new_entity();
+    world.add_component_to_entity(first, A(1));
+    let second = world.new_entity();
+    world.add_component_to_entity(second, A(2));
+
+    let query = <Query<(&A, Option<&B>)> as SystemParam>::fetch(&world);
+    assert_eq!((&query).into_iter().count(), 2);
+    drop(query);
+
+    world.add_component_to_entity(second, B);
+    let query = <Query<(&A, Option<&B>)> as SystemParam>::fetch(&world);
+    let with_b = (&query)
+        .into_iter()
+        .filter_map(|(a, b)| b.map(|_| a.0))
+        .collect::<Vec<_>>();
+    assert_eq!(with_b, vec![2]);
+}
@@ -0,0 +1,126 @@
+use std::any::Any;
+
+use super::system::{run_systems, BoxedSystem, ErrorHandler};
+use super::World;
+
+/// Types that can be used as app states with [`App::with_state`](crate::app::App::with_state).
+///
+/// This is implemented for every `Clone + PartialEq` type, so a plain enum is enough:
+/// ```rust
+/// # use carnot::prelude::*;
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum GameState {
+///     Menu,
+///     Playing,
+/// }
+///
+/// let app = App::new().with_state(GameState::Menu);
+/// ```
+pub trait States: Clone + PartialEq + 'static {}
+
+impl<T: Clone + PartialEq + 'static> States for T {}
+
+/// Resource holding the current value of the state `S`.
+pub struct State<S: States>(pub(crate) S);
+
+impl<S: States> State<S> {
+    pub fn get(&self) -> &S {
+        &self.0
+    }
+}
+
+/// Resource holding the state `S` will transition to at the end of the current frame.
+pub struct NextState<S: States>(pub(crate) Option<S>);
+
+impl<S: States> NextState<S> {
+    /// Queues a transition to `state`, replacing any previously queued transition.
+    pub fn set(&mut self, state: S) {
+        self.0 = Some(state);
+    }
+
+    pub fn get(&self) -> Option<&S> {
+        self.0.as_ref()
+    }
+}
+
+/// Schedule of systems run when the state is entered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnEnter<S: States>(pub S);
+
+/// Schedule of systems run when the state is exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnExit<S: States>(pub S);
+
+/// Run condition that is true while the state `S` is equal to `state`.
+pub fn in_state<S: States>(state: S) -> impl FnMut(&World) -> bool {
+    move |world: &World| {
+        world
+            .get_resource::<State<S>>()
+            .is_some_and(|current| current.0 == state)
+    }
+}
+
+/// The [`OnEnter`] and [`OnExit`] systems registered for a single state type.
+pub(crate) struct StateSchedules<S: States> {
+    entered: bool,
+    pub on_enter: Vec<(S, BoxedSystem)>,
+    pub on_exit: Vec<(S, BoxedSystem)>,
+}
+
+impl<S: States> Default for StateSchedules<S> {
+    fn default() -> Self {
+        Self {
+            entered: false,
+            on_enter: Default::default(),
+            on_exit: Default::default(),
+        }
+    }
+}
+
+impl<S: States> StateSchedules<S> {
+    fn run(systems: &mut [(S, BoxedSystem)], state: &S, world: &mut World, handler: ErrorHandler) {
+        run_systems(
+            systems
+                .iter_mut()
+                .filter(|(s, _)| s == state)
+                .map(|(_, system)| system),
+            world,
+            handler,
+        );
+    }
+}
+
+pub(crate) trait StateTransition {
+    /// Applies a pending [`NextState`], running the [`OnExit`] systems of the current state and
+    /// the [`OnEnter`] systems of the next.
+    ///
+    /// The first call also runs the [`OnEnter`] systems of the initial state.
+    fn apply(&mut self, world: &mut World, handler: ErrorHandler);
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<S: States> StateTransition for StateSchedules<S> {
+    fn apply(&mut self, world: &mut World, handler: ErrorHandler) {
+        if !self.entered {
+            self.entered = true;
+            let initial = world.get_resource::<State<S>>().unwrap().0.clone();
+            Self::run(&mut self.on_enter, &initial, world, handler);
+        }
+
+        let Some(next) = world.get_resource_mut::<NextState<S>>().unwrap().0.take() else {
+            return;
+        };
+        let current = world.get_resource::<State<S>>().unwrap().0.clone();
+        if next == current {
+            return;
+        }
+
+        Self::run(&mut self.on_exit, &current, world, handler);
+        world.get_resource_mut::<State<S>>().unwrap().0 = next.clone();
+        Self::run(&mut self.on_enter, &next, world, handler);
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
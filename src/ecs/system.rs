@@ -1,20 +1,42 @@
 use std::marker::PhantomData;
 
+pub use condition::{Condition, ConditionalSystem, IntoConditionalSystem};
+pub use error::{BoxError, ErrorHandler, SystemError, SystemOutput, SystemResult};
 pub use system_param::{SystemOrWorldParam, SystemParam, SystemParamItem};
 
 use super::World;
 
 use self::system_param_function::SystemParamFunction;
 
+mod condition;
+pub mod error;
 mod system_param;
 mod system_param_function;
 
 pub trait System {
-    fn run(&mut self, world: &mut World);
+    /// The name of the system, used when reporting errors.
+    fn name(&self) -> &'static str;
+    fn run(&mut self, world: &mut World) -> SystemResult;
 }
 
 pub type BoxedSystem = Box<dyn System>;
 
+/// Runs each system in order, passing any error a system returns to `handler`.
+pub fn run_systems<'s>(
+    systems: impl IntoIterator<Item = &'s mut BoxedSystem>,
+    world: &mut World,
+    handler: ErrorHandler,
+) {
+    for system in systems {
+        if let Err(error) = system.run(world) {
+            handler(SystemError {
+                system: system.name(),
+                error,
+            });
+        }
+    }
+}
+
 pub struct SystemParamFunctionHolder<F, M>
 where
     F: SystemParamFunction<M>,
@@ -29,9 +51,13 @@ where
     F: SystemParamFunction<M>,
     M: 'static,
 {
-    fn run(&mut self, world: &mut World) {
+    fn name(&self) -> &'static str {
+        std::any::type_name::<F>()
+    }
+
+    fn run(&mut self, world: &mut World) -> SystemResult {
         let param = F::Param::fetch(world);
-        self.func.run(param);
+        self.func.run(param)
     }
 }
 
@@ -55,15 +81,15 @@ where
 }
 
 pub trait WorldParamFunction {
-    fn run(&mut self, world: &mut World);
+    fn run(&mut self, world: &mut World) -> SystemResult;
 }
 
-impl<F> WorldParamFunction for F
+impl<F, Out: SystemOutput> WorldParamFunction for F
 where
-    F: for<'w> FnMut(&'w mut World),
+    F: for<'w> FnMut(&'w mut World) -> Out,
 {
-    fn run(&mut self, world: &mut World) {
-        self(world)
+    fn run(&mut self, world: &mut World) -> SystemResult {
+        self(world).into_result()
     }
 }
 
@@ -88,8 +114,12 @@ impl<F> System for WorldParamFunctionHolder<F>
 where
     F: WorldParamFunction,
 {
-    fn run(&mut self, world: &mut World) {
-        self.func.run(world);
+    fn name(&self) -> &'static str {
+        std::any::type_name::<F>()
+    }
+
+    fn run(&mut self, world: &mut World) -> SystemResult {
+        self.func.run(world)
     }
 }
 
@@ -98,5 +128,26 @@ fn test_0() {
     fn test() {}
     let mut system = IntoSystem::into_system(test);
     let mut world = World::default();
-    system.run(&mut world);
+    system.run(&mut world).unwrap();
+}
+
+#[test]
+fn test_result() {
+    #[derive(Debug)]
+    struct Failure;
+    impl std::fmt::Display for Failure {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "failure")
+        }
+    }
+    impl std::error::Error for Failure {}
+
+    fn fallible() -> Result<(), Failure> {
+        Err(Failure)
+    }
+    let mut system = IntoSystem::into_system(fallible);
+    let mut world = World::default();
+    let error = system.run(&mut world).unwrap_err();
+    assert_eq!(error.to_string(), "failure");
+    assert!(system.name().ends_with("fallible"));
 }
@@ -0,0 +1,76 @@
+use crate::ecs::World;
+
+use super::{BoxedSystem, IntoSystem, System, SystemOrWorldParam, SystemResult};
+
+/// A predicate over the [`World`] that decides whether a system runs.
+pub trait Condition: 'static {
+    fn evaluate(&mut self, world: &World) -> bool;
+}
+
+impl<F> Condition for F
+where
+    F: FnMut(&World) -> bool + 'static,
+{
+    fn evaluate(&mut self, world: &World) -> bool {
+        self(world)
+    }
+}
+
+/// A system that only runs when its [`Condition`] holds. Created with
+/// [`IntoConditionalSystem::run_if`].
+pub struct ConditionalSystem {
+    system: BoxedSystem,
+    condition: Box<dyn Condition>,
+}
+
+impl System for ConditionalSystem {
+    fn name(&self) -> &'static str {
+        self.system.name()
+    }
+
+    fn run(&mut self, world: &mut World) -> SystemResult {
+        if self.condition.evaluate(world) {
+            self.system.run(world)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl SystemOrWorldParam for ConditionalSystem {}
+
+impl IntoSystem<ConditionalSystem> for ConditionalSystem {
+    type System = Self;
+
+    fn into_system(self) -> Self::System {
+        self
+    }
+}
+
+pub trait IntoConditionalSystem<M>: IntoSystem<M> + Sized {
+    /// Wraps the system so that it only runs while `condition` evaluates to `true`.
+    fn run_if(self, condition: impl Condition) -> ConditionalSystem {
+        ConditionalSystem {
+            system: Box::new(self.into_system()),
+            condition: Box::new(condition),
+        }
+    }
+}
+
+impl<F: IntoSystem<M>, M> IntoConditionalSystem<M> for F {}
+
+#[test]
+fn test_run_if() {
+    let mut world = World::default();
+    world.add_resource(0u32);
+    world.add_resource(false);
+
+    let mut system = (|mut count: crate::ecs::resource::ResMut<u32>| *count += 1)
+        .run_if(|world: &World| *world.get_resource::<bool>().unwrap());
+    system.run(&mut world).unwrap();
+    assert_eq!(*world.get_resource::<u32>().unwrap(), 0);
+
+    *world.get_resource_mut::<bool>().unwrap() = true;
+    system.run(&mut world).unwrap();
+    assert_eq!(*world.get_resource::<u32>().unwrap(), 1);
+}
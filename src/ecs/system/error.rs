@@ -0,0 +1,60 @@
+use std::fmt;
+
+/// The error type systems can return, boxed so that systems with different error types can share
+/// a schedule.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// The result of running a [`System`](super::System).
+pub type SystemResult = Result<(), BoxError>;
+
+/// An error returned by a system, along with the name of the system that returned it.
+#[derive(Debug)]
+pub struct SystemError {
+    pub system: &'static str,
+    pub error: BoxError,
+}
+
+impl fmt::Display for SystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "system `{}` failed: {}", self.system, self.error)
+    }
+}
+
+impl std::error::Error for SystemError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+/// Decides what happens when a system returns an error. See [`log`], [`panic`] and [`ignore`].
+pub type ErrorHandler = fn(SystemError);
+
+/// Prints the error to stderr and carries on with the next system.
+pub fn log(error: SystemError) {
+    eprintln!("{}", error);
+}
+
+/// Panics with the error.
+pub fn panic(error: SystemError) {
+    panic!("{}", error);
+}
+
+/// Silently discards the error.
+pub fn ignore(_error: SystemError) {}
+
+/// Return types allowed for system functions: `()` or `Result<(), E>` where `E: Into<BoxError>`.
+pub trait SystemOutput {
+    fn into_result(self) -> SystemResult;
+}
+
+impl SystemOutput for () {
+    fn into_result(self) -> SystemResult {
+        Ok(())
+    }
+}
+
+impl<E: Into<BoxError>> SystemOutput for Result<(), E> {
+    fn into_result(self) -> SystemResult {
+        self.map_err(Into::into)
+    }
+}
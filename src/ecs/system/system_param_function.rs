@@ -1,92 +1,110 @@
-use super::{SystemParam, SystemParamItem};
+use super::{SystemOutput, SystemParam, SystemParamItem, SystemResult};
 
 pub trait SystemParamFunction<M>: 'static {
     type Param: SystemParam;
 
-    fn run(&mut self, param: SystemParamItem<Self::Param>);
+    fn run(&mut self, param: SystemParamItem<Self::Param>) -> SystemResult;
 }
 
-impl<F: 'static> SystemParamFunction<()> for F
+impl<F: 'static, Out: SystemOutput> SystemParamFunction<()> for F
 where
-    for<'w> &'w mut F: FnMut(),
+    for<'w> &'w mut F: FnMut() -> Out,
 {
     type Param = ();
 
-    fn run(&mut self, param: SystemParamItem<Self::Param>) {
-        fn call_inner(mut f: impl FnMut()) {
-            f();
+    fn run(&mut self, param: SystemParamItem<Self::Param>) -> SystemResult {
+        fn call_inner<Out>(mut f: impl FnMut() -> Out) -> Out {
+            f()
         }
         let () = param;
-        call_inner(self)
+        call_inner(self).into_result()
     }
 }
 
-impl<F: 'static, P1: SystemParam> SystemParamFunction<(P1,)> for F
+impl<F: 'static, Out: SystemOutput, P1: SystemParam> SystemParamFunction<(P1,)> for F
 where
-    for<'w> &'w mut F: FnMut(P1) + FnMut(SystemParamItem<P1>),
+    for<'w> &'w mut F: FnMut(P1) -> Out + FnMut(SystemParamItem<P1>) -> Out,
 {
     type Param = (P1,);
 
-    fn run(&mut self, param: SystemParamItem<(P1,)>) {
-        fn call_inner<P1>(mut f: impl FnMut(P1), p1: P1) {
-            f(p1);
+    fn run(&mut self, param: SystemParamItem<(P1,)>) -> SystemResult {
+        fn call_inner<Out, P1>(mut f: impl FnMut(P1) -> Out, p1: P1) -> Out {
+            f(p1)
         }
         let (p1,) = param;
-        call_inner(self, p1)
+        call_inner(self, p1).into_result()
     }
 }
 
-impl<F: 'static, P1: SystemParam, P2: SystemParam> SystemParamFunction<(P1, P2)> for F
+impl<F: 'static, Out: SystemOutput, P1: SystemParam, P2: SystemParam> SystemParamFunction<(P1, P2)>
+    for F
 where
-    for<'w> &'w mut F: FnMut(P1, P2) + FnMut(SystemParamItem<P1>, SystemParamItem<P2>),
+    for<'w> &'w mut F:
+        FnMut(P1, P2) -> Out + FnMut(SystemParamItem<P1>, SystemParamItem<P2>) -> Out,
 {
     type Param = (P1, P2);
 
-    fn run(&mut self, param: SystemParamItem<(P1, P2)>) {
-        fn call_inner<P1, P2>(mut f: impl FnMut(P1, P2), p1: P1, p2: P2) {
-            f(p1, p2);
+    fn run(&mut self, param: SystemParamItem<(P1, P2)>) -> SystemResult {
+        fn call_inner<Out, P1, P2>(mut f: impl FnMut(P1, P2) -> Out, p1: P1, p2: P2) -> Out {
+            f(p1, p2)
         }
         let (p1, p2) = param;
-        call_inner(self, p1, p2)
+        call_inner(self, p1, p2).into_result()
     }
 }
 
-impl<F: 'static, P1: SystemParam, P2: SystemParam, P3: SystemParam>
+impl<F: 'static, Out: SystemOutput, P1: SystemParam, P2: SystemParam, P3: SystemParam>
     SystemParamFunction<(P1, P2, P3)> for F
 where
-    for<'w> &'w mut F:
-        FnMut(P1, P2, P3) + FnMut(SystemParamItem<P1>, SystemParamItem<P2>, SystemParamItem<P3>),
+    for<'w> &'w mut F: FnMut(P1, P2, P3) -> Out
+        + FnMut(SystemParamItem<P1>, SystemParamItem<P2>, SystemParamItem<P3>) -> Out,
 {
     type Param = (P1, P2, P3);
 
-    fn run(&mut self, param: SystemParamItem<(P1, P2, P3)>) {
-        fn call_inner<P1, P2, P3>(mut f: impl FnMut(P1, P2, P3), p1: P1, p2: P2, p3: P3) {
-            f(p1, p2, p3);
+    fn run(&mut self, param: SystemParamItem<(P1, P2, P3)>) -> SystemResult {
+        fn call_inner<Out, P1, P2, P3>(
+            mut f: impl FnMut(P1, P2, P3) -> Out,
+            p1: P1,
+            p2: P2,
+            p3: P3,
+        ) -> Out {
+            f(p1, p2, p3)
         }
         let (p1, p2, p3) = param;
-        call_inner(self, p1, p2, p3)
+        call_inner(self, p1, p2, p3).into_result()
     }
 }
 
-impl<F: 'static, P1: SystemParam, P2: SystemParam, P3: SystemParam, P4: SystemParam>
-    SystemParamFunction<(P1, P2, P3, P4)> for F
+impl<
+        F: 'static,
+        Out: SystemOutput,
+        P1: SystemParam,
+        P2: SystemParam,
+        P3: SystemParam,
+        P4: SystemParam,
+    > SystemParamFunction<(P1, P2, P3, P4)> for F
 where
-    for<'w> &'w mut F: FnMut(P1, P2, P3, P4)
-        + FnMut(SystemParamItem<P1>, SystemParamItem<P2>, SystemParamItem<P3>, SystemParamItem<P4>),
+    for<'w> &'w mut F: FnMut(P1, P2, P3, P4) -> Out
+        + FnMut(
+            SystemParamItem<P1>,
+            SystemParamItem<P2>,
+            SystemParamItem<P3>,
+            SystemParamItem<P4>,
+        ) -> Out,
 {
     type Param = (P1, P2, P3, P4);
 
-    fn run(&mut self, param: SystemParamItem<(P1, P2, P3, P4)>) {
-        fn call_inner<P1, P2, P3, P4>(
-            mut f: impl FnMut(P1, P2, P3, P4),
+    fn run(&mut self, param: SystemParamItem<(P1, P2, P3, P4)>) -> SystemResult {
+        fn call_inner<Out, P1, P2, P3, P4>(
+            mut f: impl FnMut(P1, P2, P3, P4) -> Out,
             p1: P1,
             p2: P2,
             p3: P3,
             p4: P4,
-        ) {
-            f(p1, p2, p3, p4);
+        ) -> Out {
+            f(p1, p2, p3, p4)
         }
         let (p1, p2, p3, p4) = param;
-        call_inner(self, p1, p2, p3, p4)
+        call_inner(self, p1, p2, p3, p4).into_result()
     }
 }
@@ -50,7 +50,7 @@ impl World {
         component_vec[entity] = Some(component);
     }
 
-    pub fn borrow_component_vec<T: 'static>(&self) -> Option<Ref<Vec<Option<T>>>> {
+    pub fn borrow_component_vec<T: 'static>(&self) -> Option<Ref<'_, Vec<Option<T>>>> {
         self.component_vecs
             .get(&TypeId::of::<T>())
             .map(|component_vec| {
@@ -63,7 +63,7 @@ impl World {
             })
     }
 
-    pub fn borrow_component_vec_mut<T: 'static>(&self) -> Option<RefMut<Vec<Option<T>>>> {
+    pub fn borrow_component_vec_mut<T: 'static>(&self) -> Option<RefMut<'_, Vec<Option<T>>>> {
         self.component_vecs
             .get(&TypeId::of::<T>())
             .map(|component_vec| {
@@ -84,7 +84,7 @@ impl World {
             .insert(TypeId::of::<T>(), RefCell::new(Box::new(resource)));
     }
 
-    pub fn get_resource<T: 'static>(&self) -> Option<Res<T>> {
+    pub fn get_resource<T: 'static>(&self) -> Option<Res<'_, T>> {
         self.resources
             .get(&TypeId::of::<T>())
             .map(|resource| {
@@ -97,7 +97,7 @@ impl World {
             .map(|borrowed| Res { value: borrowed })
     }
 
-    pub fn get_resource_mut<T: 'static>(&self) -> Option<ResMut<T>> {
+    pub fn get_resource_mut<T: 'static>(&self) -> Option<ResMut<'_, T>> {
         self.resources
             .get(&TypeId::of::<T>())
             .map(|resource| {
@@ -110,6 +110,18 @@ impl World {
             .map(|borrowed| ResMut { value: borrowed })
     }
 
+    pub fn remove_resource<T: 'static>(&mut self) -> Option<T> {
+        self.resources
+            .remove(&TypeId::of::<T>())
+            .and_then(|resource| {
+                resource
+                    .into_inner()
+                    .downcast()
+                    .ok()
+                    .map(|resource| *resource)
+            })
+    }
+
     // pub fn get_resource<T: 'static>(&self) -> Option<&T> {
     //     self.resources
     //         .get(&TypeId::of::<T>())
@@ -138,12 +150,6 @@ impl World {
     //         .unwrap()
     // }
 
-    // pub fn remove_resource<T: 'static>(&mut self) -> Option<T> {
-    //     self.resources
-    //         .remove(&TypeId::of::<T>())
-    //         .and_then(|resource| resource.downcast().ok().map(|resource| *resource))
-    // }
-
     // pub fn contains_resource<T: 'static>(&self) -> bool {
     //     self.resources.contains_key(&TypeId::of::<T>())
     // }
@@ -1,8 +1,12 @@
-pub use camera::PerspectiveCamera;
-pub use mesh::Mesh;
+pub use bounds::Aabb;
+pub use camera::{Camera, OrthographicCamera, PerspectiveCamera, Projection};
+pub use frustum::Frustum;
+pub use mesh::{Mesh, MeshAttribute, MeshError};
 pub use transform::Transform;
 
+pub mod bounds;
 pub mod camera;
+pub mod frustum;
 pub mod implicit;
 pub mod material;
 pub mod mesh;
@@ -1,16 +1,19 @@
-use glam::Mat4;
+use std::sync::Arc;
 
-use super::Transform;
+use glam::{Mat4, Vec2, Vec3};
 
-pub struct CameraTransform(pub Mat4);
+use crate::render::{texture::Texture, DepthMode};
 
-impl From<&Transform> for CameraTransform {
-    fn from(transform: &Transform) -> Self {
-        Self(transform.0)
-    }
-}
+use super::{ray::Ray, Transform};
+
+/// The camera-to-world transform of a camera entity.
+///
+/// This is a [`Transform`] like that of any other entity; the camera looks along
+/// [`Transform::forward`].
+pub type CameraTransform = Transform;
 
 /// A perspective camera with only intrinsic parameters (extrinsics should be handled by something like [Transform](crate::graphics::Transform))
+#[derive(Debug, Clone)]
 pub struct PerspectiveCamera {
     /// The field of view of the camera in the x direction in degrees.
     fov: f32,
@@ -18,8 +21,9 @@ pub struct PerspectiveCamera {
     aspect_ratio: f32,
     /// The near clipping plane of the camera.
     near: f32,
-    /// The far clipping plane of the camera.
+    /// The far clipping plane of the camera, unused with [`DepthMode::ReverseZ`].
     far: f32,
+    depth_mode: DepthMode,
     /// The most recently calculated projection matrix of the camera.
     projection_matrix: Mat4,
 }
@@ -38,10 +42,20 @@ impl PerspectiveCamera {
             fov,
             near,
             far,
+            depth_mode: DepthMode::Standard,
             projection_matrix: Mat4::perspective_rh(fov_y, aspect_ratio, near, far),
         }
     }
 
+    /// Sets the depth mode, which with [`DepthMode::ReverseZ`] moves the far plane to infinity.
+    ///
+    /// The render system matches this to the renderer's depth mode, so it only needs to be set
+    /// for cameras that are used without rendering.
+    pub fn with_depth_mode(mut self, depth_mode: DepthMode) -> Self {
+        self.update_depth_mode(depth_mode);
+        self
+    }
+
     pub fn get_aspect_ratio(&self) -> f32 {
         self.aspect_ratio
     }
@@ -59,10 +73,19 @@ impl PerspectiveCamera {
         self.far
     }
 
+    pub fn get_depth_mode(&self) -> DepthMode {
+        self.depth_mode
+    }
+
     pub fn get_projection_matrix(&self) -> Mat4 {
         self.projection_matrix
     }
 
+    pub fn update_depth_mode(&mut self, depth_mode: DepthMode) {
+        self.depth_mode = depth_mode;
+        self.recompute_projection_matrix();
+    }
+
     pub fn update_aspect_ratio(&mut self, aspect_ratio: f32) {
         self.aspect_ratio = aspect_ratio;
         self.recompute_projection_matrix();
@@ -91,10 +114,27 @@ impl PerspectiveCamera {
         self.recompute_projection_matrix();
     }
 
+    /// Returns the world-space ray from the camera through the point `ndc` in normalized device
+    /// coordinates, where `(-1, -1)` is the bottom-left of the viewport and `(1, 1)` the top-right.
+    pub fn ndc_to_ray(&self, camera_transform: &CameraTransform, ndc: Vec2) -> Ray {
+        unproject_ray(
+            &self.projection_matrix,
+            self.depth_mode,
+            camera_transform,
+            ndc,
+        )
+    }
+
     fn recompute_projection_matrix(&mut self) {
         let fov_y = Self::fov_x_deg_to_fov_y_rad(self.fov, self.aspect_ratio);
-        self.projection_matrix =
-            Mat4::perspective_rh(fov_y, self.aspect_ratio, self.near, self.far);
+        self.projection_matrix = match self.depth_mode {
+            DepthMode::Standard => {
+                Mat4::perspective_rh(fov_y, self.aspect_ratio, self.near, self.far)
+            }
+            DepthMode::ReverseZ => {
+                Mat4::perspective_infinite_reverse_rh(fov_y, self.aspect_ratio, self.near)
+            }
+        };
     }
 
     fn fov_x_deg_to_fov_y_rad(fov_x_deg: f32, aspect_ratio: f32) -> f32 {
@@ -103,6 +143,361 @@ impl PerspectiveCamera {
     }
 }
 
+/// How the extents of an [`OrthographicCamera`] follow the size of the viewport.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScalingMode {
+    /// Every pixel of the viewport covers `scale` world units, so resizing the window shows more
+    /// or less of the world.
+    WindowSize { scale: f32 },
+    /// The viewport always shows `height` world units vertically; the horizontal extent follows
+    /// the aspect ratio.
+    FixedVertical { height: f32 },
+}
+
+/// An orthographic camera with only intrinsic parameters, centered on the camera's position and
+/// looking down its -Z axis.
+#[derive(Debug, Clone)]
+pub struct OrthographicCamera {
+    scaling_mode: ScalingMode,
+    /// The size of the viewport in pixels.
+    viewport_size: Vec2,
+    /// The near clipping plane of the camera.
+    near: f32,
+    /// The far clipping plane of the camera.
+    far: f32,
+    depth_mode: DepthMode,
+    /// The most recently calculated projection matrix of the camera.
+    projection_matrix: Mat4,
+}
+
+impl OrthographicCamera {
+    /// Creates a new orthographic camera.
+    /// # Arguments
+    /// * `scaling_mode` - How the visible extents relate to the viewport size.
+    /// * `viewport_size` - The size of the viewport in pixels.
+    /// * `near` - The near clipping plane of the camera.
+    /// * `far` - The far clipping plane of the camera.
+    pub fn new(scaling_mode: ScalingMode, viewport_size: Vec2, near: f32, far: f32) -> Self {
+        let mut camera = Self {
+            scaling_mode,
+            viewport_size,
+            near,
+            far,
+            depth_mode: DepthMode::Standard,
+            projection_matrix: Mat4::IDENTITY,
+        };
+        camera.recompute_projection_matrix();
+        camera
+    }
+
+    /// Sets the depth mode, which with [`DepthMode::ReverseZ`] maps the near plane to depth `1`.
+    ///
+    /// The render system matches this to the renderer's depth mode, so it only needs to be set
+    /// for cameras that are used without rendering.
+    pub fn with_depth_mode(mut self, depth_mode: DepthMode) -> Self {
+        self.update_depth_mode(depth_mode);
+        self
+    }
+
+    pub fn get_scaling_mode(&self) -> ScalingMode {
+        self.scaling_mode
+    }
+
+    pub fn get_viewport_size(&self) -> Vec2 {
+        self.viewport_size
+    }
+
+    pub fn get_near(&self) -> f32 {
+        self.near
+    }
+
+    pub fn get_far(&self) -> f32 {
+        self.far
+    }
+
+    pub fn get_projection_matrix(&self) -> Mat4 {
+        self.projection_matrix
+    }
+
+    /// Returns the half width and half height of the visible area in world units.
+    pub fn get_half_extents(&self) -> Vec2 {
+        match self.scaling_mode {
+            ScalingMode::WindowSize { scale } => self.viewport_size * scale / 2.0,
+            ScalingMode::FixedVertical { height } => {
+                let aspect_ratio = if self.viewport_size.y > 0.0 {
+                    self.viewport_size.x / self.viewport_size.y
+                } else {
+                    1.0
+                };
+                Vec2::new(height * aspect_ratio, height) / 2.0
+            }
+        }
+    }
+
+    pub fn get_depth_mode(&self) -> DepthMode {
+        self.depth_mode
+    }
+
+    pub fn update_depth_mode(&mut self, depth_mode: DepthMode) {
+        self.depth_mode = depth_mode;
+        self.recompute_projection_matrix();
+    }
+
+    pub fn update_scaling_mode(&mut self, scaling_mode: ScalingMode) {
+        self.scaling_mode = scaling_mode;
+        self.recompute_projection_matrix();
+    }
+
+    pub fn update_viewport_size(&mut self, viewport_size: Vec2) {
+        self.viewport_size = viewport_size;
+        self.recompute_projection_matrix();
+    }
+
+    pub fn update_near(&mut self, near: f32) {
+        self.near = near;
+        self.recompute_projection_matrix();
+    }
+
+    pub fn update_far(&mut self, far: f32) {
+        self.far = far;
+        self.recompute_projection_matrix();
+    }
+
+    /// Returns the world-space ray from the camera through the point `ndc` in normalized device
+    /// coordinates, where `(-1, -1)` is the bottom-left of the viewport and `(1, 1)` the top-right.
+    pub fn ndc_to_ray(&self, camera_transform: &CameraTransform, ndc: Vec2) -> Ray {
+        unproject_ray(
+            &self.projection_matrix,
+            self.depth_mode,
+            camera_transform,
+            ndc,
+        )
+    }
+
+    fn recompute_projection_matrix(&mut self) {
+        let half = self.get_half_extents();
+        let (near, far) = match self.depth_mode {
+            DepthMode::Standard => (self.near, self.far),
+            DepthMode::ReverseZ => (self.far, self.near),
+        };
+        self.projection_matrix = Mat4::orthographic_rh(-half.x, half.x, -half.y, half.y, near, far);
+    }
+}
+
+/// Component selecting the projection of a camera entity.
+///
+/// The render system, picking and the window resize handler work with either kind of camera
+/// through this type.
+#[derive(Debug, Clone)]
+pub enum Projection {
+    Perspective(PerspectiveCamera),
+    Orthographic(OrthographicCamera),
+}
+
+impl Projection {
+    pub fn get_projection_matrix(&self) -> Mat4 {
+        match self {
+            Projection::Perspective(camera) => camera.get_projection_matrix(),
+            Projection::Orthographic(camera) => camera.get_projection_matrix(),
+        }
+    }
+
+    pub fn get_near(&self) -> f32 {
+        match self {
+            Projection::Perspective(camera) => camera.get_near(),
+            Projection::Orthographic(camera) => camera.get_near(),
+        }
+    }
+
+    pub fn get_far(&self) -> f32 {
+        match self {
+            Projection::Perspective(camera) => camera.get_far(),
+            Projection::Orthographic(camera) => camera.get_far(),
+        }
+    }
+
+    pub fn get_depth_mode(&self) -> DepthMode {
+        match self {
+            Projection::Perspective(camera) => camera.get_depth_mode(),
+            Projection::Orthographic(camera) => camera.get_depth_mode(),
+        }
+    }
+
+    pub fn update_depth_mode(&mut self, depth_mode: DepthMode) {
+        match self {
+            Projection::Perspective(camera) => camera.update_depth_mode(depth_mode),
+            Projection::Orthographic(camera) => camera.update_depth_mode(depth_mode),
+        }
+    }
+
+    /// Adapts the projection to a viewport of `width` by `height` pixels.
+    pub fn update_viewport_size(&mut self, width: f32, height: f32) {
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+        match self {
+            Projection::Perspective(camera) => camera.update_aspect_ratio(width / height),
+            Projection::Orthographic(camera) => {
+                camera.update_viewport_size(Vec2::new(width, height))
+            }
+        }
+    }
+
+    /// Returns the world-space ray from the camera through the point `ndc` in normalized device
+    /// coordinates, where `(-1, -1)` is the bottom-left of the viewport and `(1, 1)` the top-right.
+    pub fn ndc_to_ray(&self, camera_transform: &CameraTransform, ndc: Vec2) -> Ray {
+        unproject_ray(
+            &self.get_projection_matrix(),
+            self.get_depth_mode(),
+            camera_transform,
+            ndc,
+        )
+    }
+}
+
+impl From<PerspectiveCamera> for Projection {
+    fn from(camera: PerspectiveCamera) -> Self {
+        Projection::Perspective(camera)
+    }
+}
+
+impl From<OrthographicCamera> for Projection {
+    fn from(camera: OrthographicCamera) -> Self {
+        Projection::Orthographic(camera)
+    }
+}
+
+/// A rectangle of a render target, in fractions of the target's size measured from its top-left,
+/// so that it follows the target when the window is resized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl Viewport {
+    /// The whole render target.
+    pub const FULL: Self = Self {
+        position: Vec2::ZERO,
+        size: Vec2::ONE,
+    };
+
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        Self { position, size }
+    }
+
+    /// Returns the position and size of the viewport in pixels for a target of `target_size`
+    /// pixels, clamped to the target.
+    pub fn to_pixels(&self, target_size: Vec2) -> (Vec2, Vec2) {
+        let target_size = target_size.max(Vec2::ONE);
+        let position = (self.position * target_size)
+            .round()
+            .clamp(Vec2::ZERO, target_size - 1.0);
+        let size = (self.size * target_size)
+            .round()
+            .clamp(Vec2::ONE, target_size - position);
+        (position, size)
+    }
+
+    /// Converts normalized device coordinates of the whole target (e.g. from
+    /// [`InputState::cursor_ndc`](crate::input::InputState::cursor_ndc)) to those of the viewport,
+    /// for picking with [`Projection::ndc_to_ray`].
+    ///
+    /// Returns `None` if the point is outside the viewport.
+    pub fn target_ndc_to_viewport_ndc(&self, ndc: Vec2) -> Option<Vec2> {
+        // Fractions of the target from its top-left, like the viewport itself
+        let fraction = Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) / 2.0;
+        let local = (fraction - self.position) / self.size;
+        if local.cmplt(Vec2::ZERO).any() || local.cmpgt(Vec2::ONE).any() {
+            return None;
+        }
+        Some(Vec2::new(2.0 * local.x - 1.0, 1.0 - 2.0 * local.y))
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+/// Where a [`Camera`] draws to.
+#[derive(Clone, Default)]
+pub enum RenderTarget {
+    /// The window's surface.
+    #[default]
+    Window,
+    /// An offscreen texture, created with [`Texture::create_render_target`], that can be shown
+    /// elsewhere in the scene or read back.
+    Texture(Arc<Texture>),
+}
+
+/// Component for an entity that is rendered from, together with a [`Projection`] and a
+/// [`CameraTransform`].
+///
+/// Cameras are drawn in ascending `order`, with cameras that draw to textures drawn before those
+/// that draw to the window so that their output is ready to be shown.
+#[derive(Clone)]
+pub struct Camera {
+    pub viewport: Viewport,
+    pub order: i32,
+    /// The color the viewport is cleared to before drawing, or `None` to draw over what earlier
+    /// cameras drew. The depth buffer is always cleared within the viewport.
+    pub clear_color: Option<wgpu::Color>,
+    pub target: RenderTarget,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            viewport: Viewport::FULL,
+            order: 0,
+            clear_color: Some(wgpu::Color::TRANSPARENT),
+            target: RenderTarget::Window,
+        }
+    }
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn with_order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn with_clear_color(mut self, clear_color: Option<wgpu::Color>) -> Self {
+        self.clear_color = clear_color;
+        self
+    }
+
+    pub fn with_target(mut self, target: RenderTarget) -> Self {
+        self.target = target;
+        self
+    }
+}
+
+/// Unprojects `ndc` at the near plane and halfway into the depth range to get the ray through it.
+/// The far plane itself is not used since it may be at infinity.
+fn unproject_ray(
+    projection_matrix: &Mat4,
+    depth_mode: DepthMode,
+    camera_transform: &CameraTransform,
+    ndc: Vec2,
+) -> Ray {
+    let inv_view_proj = camera_transform.compute_matrix() * projection_matrix.inverse();
+    let near = inv_view_proj.project_point3(Vec3::new(ndc.x, ndc.y, depth_mode.near()));
+    let middle = inv_view_proj.project_point3(Vec3::new(ndc.x, ndc.y, 0.5));
+    Ray::new(near, (middle - near).normalize())
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct CameraUniform {
@@ -125,3 +520,71 @@ impl CameraUniform {
         }
     }
 }
+
+#[test]
+fn test_ndc_to_ray() {
+    let camera = PerspectiveCamera::new(90.0, 1.0, 0.1, 100.0);
+    let camera_transform = CameraTransform::from_xyz(0.0, 1.0, 0.0);
+
+    let ray = camera.ndc_to_ray(&camera_transform, Vec2::ZERO);
+    assert!(ray.direction.abs_diff_eq(Vec3::NEG_Z, 1e-5));
+    assert!(ray.origin.abs_diff_eq(Vec3::new(0.0, 1.0, -0.1), 1e-5));
+
+    // With a 90 degree field of view, the right edge of the viewport is 45 degrees off-axis
+    let ray = camera.ndc_to_ray(&camera_transform, Vec2::new(1.0, 0.0));
+    assert!(ray
+        .direction
+        .abs_diff_eq(Vec3::new(1.0, 0.0, -1.0).normalize(), 1e-5));
+}
+
+#[test]
+fn test_orthographic_camera() {
+    let mut camera = Projection::from(OrthographicCamera::new(
+        ScalingMode::FixedVertical { height: 10.0 },
+        Vec2::new(800.0, 400.0),
+        0.1,
+        100.0,
+    ));
+    let camera_transform = CameraTransform::IDENTITY;
+
+    let ray = camera.ndc_to_ray(&camera_transform, Vec2::new(1.0, 1.0));
+    assert!(ray.direction.abs_diff_eq(Vec3::NEG_Z, 1e-5));
+    assert!(ray.origin.abs_diff_eq(Vec3::new(10.0, 5.0, -0.1), 1e-4));
+
+    camera.update_viewport_size(400.0, 400.0);
+    let ray = camera.ndc_to_ray(&camera_transform, Vec2::new(1.0, 1.0));
+    assert!(ray.origin.abs_diff_eq(Vec3::new(5.0, 5.0, -0.1), 1e-4));
+}
+
+#[test]
+fn test_viewport_ndc() {
+    let viewport = Viewport::new(Vec2::new(0.5, 0.0), Vec2::new(0.5, 0.5));
+    assert_eq!(
+        viewport.target_ndc_to_viewport_ndc(Vec2::new(0.5, 0.5)),
+        Some(Vec2::ZERO)
+    );
+    assert_eq!(
+        viewport.target_ndc_to_viewport_ndc(Vec2::new(1.0, 0.0)),
+        Some(Vec2::new(1.0, -1.0))
+    );
+    assert_eq!(viewport.target_ndc_to_viewport_ndc(Vec2::ZERO - 0.5), None);
+    assert_eq!(
+        viewport.to_pixels(Vec2::new(800.0, 600.0)),
+        (Vec2::new(400.0, 0.0), Vec2::new(400.0, 300.0))
+    );
+}
+
+#[test]
+fn test_reverse_z() {
+    let camera = PerspectiveCamera::new(90.0, 1.0, 0.1, 100.0).with_depth_mode(DepthMode::ReverseZ);
+    let projection = camera.get_projection_matrix();
+    let depth = |z: f32| projection.project_point3(Vec3::new(0.0, 0.0, z)).z;
+    assert!((depth(-0.1) - 1.0).abs() < 1e-5);
+    assert!(depth(-1000.0) > depth(-100_000.0));
+    assert!(depth(-100_000.0) > 0.0);
+
+    let camera_transform = CameraTransform::IDENTITY;
+    let ray = camera.ndc_to_ray(&camera_transform, Vec2::ZERO);
+    assert!(ray.direction.abs_diff_eq(Vec3::NEG_Z, 1e-5));
+    assert!(ray.origin.abs_diff_eq(Vec3::new(0.0, 0.0, -0.1), 1e-5));
+}
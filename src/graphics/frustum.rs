@@ -0,0 +1,93 @@
+use glam::{Mat4, Vec3, Vec4, Vec4Swizzles};
+
+use super::{bounds::Aabb, camera::CameraTransform, Projection};
+
+/// The volume visible to a camera, as six planes whose normals point inwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// The left, right, bottom, top, near and far planes as `(normal, distance)`, so that a
+    /// point `p` is on the inner side of a plane if `normal.dot(p) + distance >= 0`.
+    pub planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extracts the frustum from a view-projection matrix with a `[0, 1]` depth range.
+    ///
+    /// This works in either [`DepthMode`](crate::render::DepthMode). With an infinite far plane,
+    /// the far plane degenerates to one that every point is inside of.
+    pub fn from_view_projection(view_projection: &Mat4) -> Self {
+        let row = |i| view_projection.row(i);
+        let planes = [
+            row(3) + row(0),
+            row(3) - row(0),
+            row(3) + row(1),
+            row(3) - row(1),
+            row(2),
+            row(3) - row(2),
+        ]
+        .map(|plane| {
+            let length = plane.xyz().length();
+            if length > f32::EPSILON {
+                plane / length
+            } else {
+                plane
+            }
+        });
+        Self { planes }
+    }
+
+    pub fn from_camera(projection: &Projection, camera_transform: &CameraTransform) -> Self {
+        Self::from_view_projection(
+            &(projection.get_projection_matrix() * camera_transform.compute_matrix().inverse()),
+        )
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.xyz().dot(point) + plane.w >= 0.0)
+    }
+
+    /// Whether any part of the world-space box may be inside the frustum.
+    ///
+    /// This is conservative: boxes near the frustum's corners may be reported as intersecting
+    /// even though they are just outside.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        let center = aabb.center();
+        let half_extents = aabb.half_extents();
+        self.planes.iter().all(|plane| {
+            let normal = plane.xyz();
+            // The distance of the box's furthest corner along the normal
+            let radius = normal.abs().dot(half_extents);
+            normal.dot(center) + plane.w >= -radius
+        })
+    }
+}
+
+#[test]
+fn test_frustum_culling() {
+    use super::PerspectiveCamera;
+    use crate::render::DepthMode;
+
+    let camera_transform = CameraTransform::IDENTITY;
+    let unit_box = |center: Vec3| Aabb::new(center - 0.5, center + 0.5);
+
+    for depth_mode in [DepthMode::Standard, DepthMode::ReverseZ] {
+        let projection = Projection::from(
+            PerspectiveCamera::new(90.0, 1.0, 0.1, 100.0).with_depth_mode(depth_mode),
+        );
+        let frustum = Frustum::from_camera(&projection, &camera_transform);
+
+        assert!(frustum.contains_point(Vec3::new(0.0, 0.0, -10.0)));
+        assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, 10.0)));
+        assert!(frustum.intersects_aabb(&unit_box(Vec3::new(0.0, 0.0, -5.0))));
+        assert!(!frustum.intersects_aabb(&unit_box(Vec3::new(0.0, 0.0, 5.0))));
+        assert!(!frustum.intersects_aabb(&unit_box(Vec3::new(20.0, 0.0, -5.0))));
+        // Straddling the left edge
+        assert!(frustum.intersects_aabb(&unit_box(Vec3::new(-5.2, 0.0, -5.0))));
+        assert_eq!(
+            frustum.intersects_aabb(&unit_box(Vec3::new(0.0, 0.0, -1000.0))),
+            depth_mode == DepthMode::ReverseZ
+        );
+    }
+}
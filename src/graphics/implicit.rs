@@ -8,15 +8,10 @@ pub struct ImplicitSphere;
 
 impl Implicit for ImplicitSphere {
     fn intersect_world(&self, world_ray: &Ray, self_transform: &Transform) -> Option<f32> {
+        let world_to_local = self_transform.compute_matrix().inverse();
         let local_ray = Ray {
-            origin: self_transform
-                .0
-                .inverse()
-                .transform_point3(world_ray.origin),
-            direction: self_transform
-                .0
-                .inverse()
-                .transform_vector3(world_ray.direction),
+            origin: world_to_local.transform_point3(world_ray.origin),
+            direction: world_to_local.transform_vector3(world_ray.direction),
         };
 
         let a = local_ray.direction.length_squared();
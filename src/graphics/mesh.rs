@@ -1,31 +1,664 @@
-use crate::render::vertex::Vertex;
+use std::collections::{BTreeMap, HashMap};
 
+use glam::Vec3;
+use thiserror::Error;
+use wgpu::VertexFormat;
+
+use super::bounds::Aabb;
+use super::transform::Transform;
+use crate::render::vertex::VertexLayout;
+
+pub use attribute::{MeshAttribute, VertexAttributeValues};
+
+mod attribute;
+
+/// Indexed triangles with a set of named vertex attribute streams of the same length.
+///
+/// Most meshes have a [position](MeshAttribute::POSITION), [normal](MeshAttribute::NORMAL) and
+/// [texture coordinates](MeshAttribute::TEX_COORDS), which can be built from [`MeshVertex`]es.
+/// Pipelines pick the attributes they need with a [`VertexLayout`].
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Mesh {
-    pub vertices: Vec<MeshVertex>,
+    attributes: BTreeMap<&'static str, (MeshAttribute, VertexAttributeValues)>,
     pub indices: Vec<u32>,
 }
 
-#[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum MeshError {
+    #[error("Index count {0} is not a multiple of 3")]
+    IncompleteTriangle(usize),
+    #[error("Index {index} is out of range for {vertices} vertices")]
+    IndexOutOfRange { index: u32, vertices: usize },
+    #[error("Triangle {0} is degenerate")]
+    DegenerateTriangle(usize),
+    #[error("Vertex {0} has a NaN or infinite attribute")]
+    NonFinite(usize),
+    #[error("Mesh has no `{0}` attribute")]
+    MissingAttribute(&'static str),
+    #[error("Attribute `{name}` is {found:?} but {expected:?} is required")]
+    AttributeFormat {
+        name: &'static str,
+        expected: VertexFormat,
+        found: VertexFormat,
+    },
+    #[error("Attribute `{name}` has {len} values for {vertices} vertices")]
+    AttributeLength {
+        name: &'static str,
+        len: usize,
+        vertices: usize,
+    },
+    #[error("Failed to generate tangents")]
+    TangentGeneration,
+}
+
+/// A vertex with the standard attributes, for building meshes with [`Mesh::from_vertices`] and
+/// [`Mesh::push_vertex`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct MeshVertex {
     pub position: [f32; 3],
     pub normal: [f32; 3],
     pub tex_coords: [f32; 2],
 }
 
-impl Vertex for MeshVertex {
-    fn desc() -> wgpu::VertexBufferLayout<'static> {
-        use std::mem;
-        wgpu::VertexBufferLayout {
-            array_stride: mem::size_of::<MeshVertex>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &ATTRIBS,
+impl Mesh {
+    /// A mesh with the [position](MeshAttribute::POSITION), [normal](MeshAttribute::NORMAL) and
+    /// [texture coordinates](MeshAttribute::TEX_COORDS) of `vertices`.
+    pub fn from_vertices(vertices: &[MeshVertex], indices: Vec<u32>) -> Self {
+        Self::default()
+            .with_attribute(
+                MeshAttribute::POSITION,
+                vertices
+                    .iter()
+                    .map(|vertex| vertex.position)
+                    .collect::<Vec<_>>(),
+            )
+            .with_attribute(
+                MeshAttribute::NORMAL,
+                vertices
+                    .iter()
+                    .map(|vertex| vertex.normal)
+                    .collect::<Vec<_>>(),
+            )
+            .with_attribute(
+                MeshAttribute::TEX_COORDS,
+                vertices
+                    .iter()
+                    .map(|vertex| vertex.tex_coords)
+                    .collect::<Vec<_>>(),
+            )
+            .with_indices(indices)
+    }
+
+    pub fn with_attribute(
+        mut self,
+        attribute: MeshAttribute,
+        values: impl Into<VertexAttributeValues>,
+    ) -> Self {
+        self.insert_attribute(attribute, values);
+        self
+    }
+
+    pub fn with_indices(mut self, indices: Vec<u32>) -> Self {
+        self.indices = indices;
+        self
+    }
+
+    /// Adds or replaces the values of the attribute with the name of `attribute`.
+    ///
+    /// # Panics
+    ///
+    /// If the values are not in the format of `attribute`.
+    pub fn insert_attribute(
+        &mut self,
+        attribute: MeshAttribute,
+        values: impl Into<VertexAttributeValues>,
+    ) {
+        let values = values.into();
+        assert_eq!(
+            values.format(),
+            attribute.format,
+            "Wrong format for attribute `{}`",
+            attribute.name
+        );
+        self.attributes.insert(attribute.name, (attribute, values));
+    }
+
+    /// The values of `attribute`, if the mesh has it in the same format.
+    pub fn attribute(&self, attribute: MeshAttribute) -> Option<&VertexAttributeValues> {
+        self.attributes
+            .get(attribute.name)
+            .filter(|(existing, _)| *existing == attribute)
+            .map(|(_, values)| values)
+    }
+
+    /// Like [`Mesh::attribute`]. Changing the number of values makes the mesh invalid until the
+    /// other attributes match.
+    pub fn attribute_mut(
+        &mut self,
+        attribute: MeshAttribute,
+    ) -> Option<&mut VertexAttributeValues> {
+        self.attributes
+            .get_mut(attribute.name)
+            .filter(|(existing, _)| *existing == attribute)
+            .map(|(_, values)| values)
+    }
+
+    pub fn remove_attribute(&mut self, attribute: MeshAttribute) -> Option<VertexAttributeValues> {
+        self.attribute(attribute)?;
+        self.attributes
+            .remove(attribute.name)
+            .map(|(_, values)| values)
+    }
+
+    pub fn contains_attribute(&self, attribute: MeshAttribute) -> bool {
+        self.attribute(attribute).is_some()
+    }
+
+    /// All attributes, sorted by name.
+    pub fn attributes(&self) -> impl Iterator<Item = (MeshAttribute, &VertexAttributeValues)> {
+        self.attributes
+            .values()
+            .map(|(attribute, values)| (*attribute, values))
+    }
+
+    /// The number of positions, which every other attribute has to match.
+    pub fn vertex_count(&self) -> usize {
+        self.positions().len()
+    }
+
+    /// The positions, or nothing if the mesh has no [`MeshAttribute::POSITION`].
+    pub fn positions(&self) -> &[[f32; 3]] {
+        self.attribute(MeshAttribute::POSITION)
+            .and_then(VertexAttributeValues::as_float32x3)
+            .unwrap_or_default()
+    }
+
+    pub fn normals(&self) -> Option<&[[f32; 3]]> {
+        self.attribute(MeshAttribute::NORMAL)?.as_float32x3()
+    }
+
+    pub fn tex_coords(&self) -> Option<&[[f32; 2]]> {
+        self.attribute(MeshAttribute::TEX_COORDS)?.as_float32x2()
+    }
+
+    /// The standard attributes of the vertex at `index`, with zeros for missing attributes.
+    pub fn vertex(&self, index: usize) -> MeshVertex {
+        MeshVertex {
+            position: self.positions()[index],
+            normal: self.normals().map_or([0.0; 3], |normals| normals[index]),
+            tex_coords: self
+                .tex_coords()
+                .map_or([0.0; 2], |tex_coords| tex_coords[index]),
+        }
+    }
+
+    /// Appends a vertex with the standard attributes of `vertex` and zeros for all others, and
+    /// returns its index.
+    pub fn push_vertex(&mut self, vertex: MeshVertex) -> u32 {
+        let index = self.vertex_count();
+        if let VertexAttributeValues::Float32x3(positions) =
+            self.attribute_or_zeros(MeshAttribute::POSITION, index)
+        {
+            positions.push(vertex.position);
+        }
+        if let VertexAttributeValues::Float32x3(normals) =
+            self.attribute_or_zeros(MeshAttribute::NORMAL, index)
+        {
+            normals.push(vertex.normal);
+        }
+        if let VertexAttributeValues::Float32x2(tex_coords) =
+            self.attribute_or_zeros(MeshAttribute::TEX_COORDS, index)
+        {
+            tex_coords.push(vertex.tex_coords);
+        }
+        for (_, values) in self.attributes.values_mut() {
+            values.resize_zeroed(index + 1);
+        }
+        index as u32
+    }
+
+    /// The values of `attribute`, which is added with `vertices` zeros if the mesh has none.
+    fn attribute_or_zeros(
+        &mut self,
+        attribute: MeshAttribute,
+        vertices: usize,
+    ) -> &mut VertexAttributeValues {
+        &mut self
+            .attributes
+            .entry(attribute.name)
+            .or_insert_with(|| {
+                let mut values = VertexAttributeValues::empty(attribute.format);
+                values.resize_zeroed(vertices);
+                (attribute, values)
+            })
+            .1
+    }
+
+    /// The bounds of the vertices in local space, or `None` if the mesh has none.
+    pub fn compute_aabb(&self) -> Option<Aabb> {
+        Aabb::from_points(self.positions().iter().copied().map(Vec3::from))
+    }
+
+    /// Checks that the mesh is well-formed: [`Mesh::validate_drawable`] passes and no triangle has
+    /// zero area.
+    pub fn validate(&self) -> Result<(), MeshError> {
+        self.validate_drawable()?;
+        for (triangle, indices) in self.indices.chunks_exact(3).enumerate() {
+            if self.face_normal(indices) == Vec3::ZERO {
+                return Err(MeshError::DegenerateTriangle(triangle));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the mesh can be uploaded and drawn: every attribute has a value per vertex,
+    /// the indices form whole triangles within range and every floating-point attribute is
+    /// finite. Unlike [`Mesh::validate`], triangles with zero area are allowed, as they are
+    /// common in imported models and simply don't rasterize.
+    pub fn validate_drawable(&self) -> Result<(), MeshError> {
+        if !self.contains_attribute(MeshAttribute::POSITION) {
+            return Err(MeshError::MissingAttribute(MeshAttribute::POSITION.name));
+        }
+        let vertices = self.vertex_count();
+        for (attribute, values) in self.attributes() {
+            if values.len() != vertices {
+                return Err(MeshError::AttributeLength {
+                    name: attribute.name,
+                    len: values.len(),
+                    vertices,
+                });
+            }
+        }
+        if !self.indices.len().is_multiple_of(3) {
+            return Err(MeshError::IncompleteTriangle(self.indices.len()));
+        }
+        if let Some(vertex) = self
+            .attributes()
+            .filter_map(|(attribute, values)| {
+                let components = attribute.format.size() as usize / 4;
+                let component = values.as_floats()?.iter().position(|x| !x.is_finite())?;
+                Some(component / components)
+            })
+            .min()
+        {
+            return Err(MeshError::NonFinite(vertex));
+        }
+        if let Some(&index) = self
+            .indices
+            .iter()
+            .find(|&&index| index as usize >= vertices)
+        {
+            return Err(MeshError::IndexOutOfRange { index, vertices });
+        }
+        Ok(())
+    }
+
+    /// Checks that the mesh has every attribute of `layout` in the right format, with a value per
+    /// vertex, so that [`Mesh::vertex_buffer_data`] succeeds.
+    pub fn validate_layout(&self, layout: &VertexLayout) -> Result<(), MeshError> {
+        let vertices = self.vertex_count();
+        for &(attribute, _) in layout.mesh_attributes() {
+            let (existing, values) = self
+                .attributes
+                .get(attribute.name)
+                .ok_or(MeshError::MissingAttribute(attribute.name))?;
+            if existing.format != attribute.format {
+                return Err(MeshError::AttributeFormat {
+                    name: attribute.name,
+                    expected: attribute.format,
+                    found: existing.format,
+                });
+            }
+            if values.len() != vertices {
+                return Err(MeshError::AttributeLength {
+                    name: attribute.name,
+                    len: values.len(),
+                    vertices,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Interleaves the attributes of `layout` into the contents of a vertex buffer.
+    pub fn vertex_buffer_data(&self, layout: &VertexLayout) -> Result<Vec<u8>, MeshError> {
+        self.validate_layout(layout)?;
+        let stride = layout.array_stride() as usize;
+        let mut data = vec![0; self.vertex_count() * stride];
+        for &(attribute, offset) in layout.mesh_attributes() {
+            let (_, values) = &self.attributes[attribute.name];
+            let size = attribute.format.size() as usize;
+            for (vertex, value) in values.as_bytes().chunks_exact(size).enumerate() {
+                let start = vertex * stride + offset as usize;
+                data[start..start + size].copy_from_slice(value);
+            }
         }
+        Ok(data)
+    }
+
+    /// Replaces the normals with the area-weighted average of the adjacent faces. Only triangles
+    /// that share a vertex are smoothed together, so [`Mesh::weld`] split vertices first.
+    ///
+    /// # Panics
+    ///
+    /// If an index is out of range, so check untrusted meshes with [`Mesh::validate_drawable`]
+    /// first.
+    pub fn compute_smooth_normals(&mut self) {
+        let mut normals = vec![Vec3::ZERO; self.vertex_count()];
+        for triangle in self.indices.chunks_exact(3) {
+            let normal = self.face_normal(triangle);
+            for &index in triangle {
+                normals[index as usize] += normal;
+            }
+        }
+        self.insert_attribute(
+            MeshAttribute::NORMAL,
+            normals
+                .into_iter()
+                .map(|normal| normal.normalize_or_zero().to_array())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    /// Gives every triangle its own vertices and sets their normals to the face normal.
+    ///
+    /// # Panics
+    ///
+    /// If an index is out of range, so check untrusted meshes with [`Mesh::validate_drawable`]
+    /// first.
+    pub fn compute_flat_normals(&mut self) {
+        let normals = self
+            .indices
+            .chunks_exact(3)
+            .flat_map(|triangle| [self.face_normal(triangle).normalize_or_zero().to_array(); 3])
+            .collect::<Vec<_>>();
+        for (_, values) in self.attributes.values_mut() {
+            *values = values.gather(self.indices.iter().map(|&index| index as usize));
+        }
+        self.indices = (0..normals.len() as u32).collect();
+        self.insert_attribute(MeshAttribute::NORMAL, normals);
+    }
+
+    /// Generates [tangents](MeshAttribute::TANGENT) from the positions, normals and texture
+    /// coordinates with MikkTSpace, the convention used by glTF and most baking tools.
+    ///
+    /// Vertices are expected to be split wherever the tangent frame is discontinuous, as they
+    /// already are at UV seams.
+    pub fn generate_tangents(&mut self) -> Result<(), MeshError> {
+        self.validate()?;
+        let mut geometry = TangentGeometry {
+            positions: self.positions(),
+            normals: self
+                .normals()
+                .ok_or(MeshError::MissingAttribute(MeshAttribute::NORMAL.name))?,
+            tex_coords: self
+                .tex_coords()
+                .ok_or(MeshError::MissingAttribute(MeshAttribute::TEX_COORDS.name))?,
+            indices: &self.indices,
+            tangents: vec![[0.0; 4]; self.vertex_count()],
+        };
+        if !bevy_mikktspace::generate_tangents(&mut geometry) {
+            return Err(MeshError::TangentGeneration);
+        }
+        let tangents = geometry.tangents;
+        self.insert_attribute(MeshAttribute::TANGENT, tangents);
+        Ok(())
+    }
+
+    /// Merges vertices whose attributes are all equal after snapping floating-point values to a
+    /// grid of `epsilon`, and removes the triangles that collapse as a result. An `epsilon` of
+    /// zero only merges exact duplicates.
+    pub fn weld(&mut self, epsilon: f32) {
+        let streams = self
+            .attributes()
+            .map(|(attribute, values)| (attribute.format.size() as usize, values))
+            .collect::<Vec<_>>();
+        let key = |vertex: usize| {
+            let mut key = Vec::new();
+            for (size, values) in &streams {
+                let bytes = &values.as_bytes()[vertex * size..(vertex + 1) * size];
+                if values.as_floats().is_none() {
+                    key.extend(bytes.iter().map(|&byte| byte as i64));
+                    continue;
+                }
+                key.extend(bytes.chunks_exact(4).map(|bytes| {
+                    let x = f32::from_le_bytes(bytes.try_into().unwrap());
+                    if epsilon > 0.0 {
+                        (x / epsilon).round() as i64
+                    } else {
+                        // Adding zero turns -0.0 into 0.0.
+                        (x + 0.0).to_bits() as i64
+                    }
+                }));
+            }
+            key
+        };
+
+        let mut kept = Vec::new();
+        let mut welded = HashMap::new();
+        let remap = (0..self.vertex_count())
+            .map(|vertex| {
+                *welded.entry(key(vertex)).or_insert_with(|| {
+                    kept.push(vertex);
+                    kept.len() as u32 - 1
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut indices = Vec::with_capacity(self.indices.len());
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| remap[triangle[i] as usize]);
+            if a != b && b != c && a != c {
+                indices.extend([a, b, c]);
+            }
+        }
+        for (_, values) in self.attributes.values_mut() {
+            *values = values.gather(kept.iter().copied());
+        }
+        self.indices = indices;
+    }
+
+    /// Applies `transform` to the positions, normals and tangents. Mirroring transforms also
+    /// flip the winding so that front faces stay counter-clockwise.
+    pub fn transform(&mut self, transform: &Transform) {
+        let mirrored = transform.scale.x * transform.scale.y * transform.scale.z < 0.0;
+        if let Some(positions) = self
+            .attribute_mut(MeshAttribute::POSITION)
+            .and_then(VertexAttributeValues::as_float32x3_mut)
+        {
+            for position in positions {
+                *position = transform.transform_point(Vec3::from(*position)).to_array();
+            }
+        }
+        let normal_matrix = transform.compute_normal_matrix();
+        if let Some(normals) = self
+            .attribute_mut(MeshAttribute::NORMAL)
+            .and_then(VertexAttributeValues::as_float32x3_mut)
+        {
+            for normal in normals {
+                *normal = (normal_matrix * Vec3::from(*normal))
+                    .normalize_or_zero()
+                    .to_array();
+            }
+        }
+        if let Some(tangents) = self
+            .attribute_mut(MeshAttribute::TANGENT)
+            .and_then(VertexAttributeValues::as_float32x4_mut)
+        {
+            for tangent in tangents {
+                let direction = transform.rotation * (transform.scale * Vec3::from_slice(tangent));
+                let handedness = if mirrored { -tangent[3] } else { tangent[3] };
+                *tangent = direction.normalize_or_zero().extend(handedness).to_array();
+            }
+        }
+        if mirrored {
+            self.flip_winding();
+        }
+    }
+
+    /// Appends `other` with `transform` applied, e.g. to batch static geometry into one mesh.
+    /// Attributes that only one of the meshes has are filled with zeros for the other.
+    pub fn merge(&mut self, other: &Mesh, transform: &Transform) {
+        let mut other = other.clone();
+        other.transform(transform);
+        let offset = self.vertex_count();
+        for (attribute, values) in other.attributes() {
+            self.attribute_or_zeros(attribute, offset).append(values);
+        }
+        let vertices = offset + other.vertex_count();
+        for (_, values) in self.attributes.values_mut() {
+            values.resize_zeroed(vertices);
+        }
+        self.indices
+            .extend(other.indices.iter().map(|index| index + offset as u32));
+    }
+
+    /// Reverses the order of every triangle, turning front faces into back faces. The normals
+    /// are left as they are.
+    pub fn flip_winding(&mut self) {
+        for triangle in self.indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+
+    /// The unnormalized normal of a counter-clockwise triangle, whose length is twice its area.
+    /// Panics if an index is out of range.
+    fn face_normal(&self, triangle: &[u32]) -> Vec3 {
+        let positions = self.positions();
+        let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i] as usize]));
+        (b - a).cross(c - a)
+    }
+}
+
+struct TangentGeometry<'a> {
+    positions: &'a [[f32; 3]],
+    normals: &'a [[f32; 3]],
+    tex_coords: &'a [[f32; 2]],
+    indices: &'a [u32],
+    tangents: Vec<[f32; 4]>,
+}
+
+impl TangentGeometry<'_> {
+    fn index(&self, face: usize, vert: usize) -> usize {
+        self.indices[face * 3 + vert] as usize
+    }
+}
+
+impl bevy_mikktspace::Geometry for TangentGeometry<'_> {
+    fn num_faces(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    fn num_vertices_of_face(&self, _face: usize) -> usize {
+        3
+    }
+
+    fn position(&self, face: usize, vert: usize) -> [f32; 3] {
+        self.positions[self.index(face, vert)]
+    }
+
+    fn normal(&self, face: usize, vert: usize) -> [f32; 3] {
+        self.normals[self.index(face, vert)]
+    }
+
+    fn tex_coord(&self, face: usize, vert: usize) -> [f32; 2] {
+        self.tex_coords[self.index(face, vert)]
+    }
+
+    fn set_tangent_encoded(&mut self, tangent: [f32; 4], face: usize, vert: usize) {
+        let index = self.index(face, vert);
+        self.tangents[index] = tangent;
     }
 }
 
-const ATTRIBS: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![
-    0 => Float32x3,
-    1 => Float32x3,
-    2 => Float32x2
-];
+#[test]
+fn test_mesh_processing() {
+    let vertex = |x: f32, y: f32| MeshVertex {
+        position: [x, y, 0.0],
+        normal: [0.0, 0.0, 1.0],
+        tex_coords: [x, y],
+    };
+    let vertices = [
+        (0.0, 0.0),
+        (1.0, 0.0),
+        (1.0, 1.0),
+        (0.0, 0.0),
+        (1.0, 1.0),
+        (0.0, 1.0),
+    ]
+    .map(|(x, y)| vertex(x, y));
+    let mut quad = Mesh::from_vertices(&vertices, (0..6).collect());
+    assert_eq!(quad.validate(), Ok(()));
+    quad.weld(1e-5);
+    assert_eq!((quad.vertex_count(), quad.indices.len()), (4, 6));
+
+    quad.generate_tangents().unwrap();
+    let tangents = |mesh: &Mesh| match mesh.attribute(MeshAttribute::TANGENT) {
+        Some(VertexAttributeValues::Float32x4(tangents)) => tangents.clone(),
+        _ => panic!("no tangents"),
+    };
+    for tangent in tangents(&quad) {
+        assert!(Vec3::from_slice(&tangent).abs_diff_eq(Vec3::X, 1e-5));
+        assert_eq!(tangent[3], 1.0);
+    }
+
+    let mut merged = quad.clone();
+    merged.merge(&quad, &Transform::from_scale(Vec3::new(-1.0, 1.0, 1.0)));
+    assert_eq!(merged.vertex_count(), 8);
+    for triangle in merged.indices.chunks_exact(3) {
+        let normal = merged.face_normal(triangle).normalize();
+        assert!(normal.abs_diff_eq(Vec3::Z, 1e-5));
+    }
+    let mirrored = tangents(&merged)[4];
+    assert!(Vec3::from_slice(&mirrored).abs_diff_eq(Vec3::NEG_X, 1e-5));
+    assert_eq!(mirrored[3], -1.0);
+
+    // Attributes that only one side has are padded with zeros
+    let colored = Mesh::from_vertices(&vertices[..3], vec![0, 1, 2])
+        .with_attribute(MeshAttribute::COLOR, vec![[1.0; 4]; 3]);
+    merged.merge(&colored, &Transform::IDENTITY);
+    assert_eq!(merged.validate(), Ok(()));
+    assert_eq!(merged.attribute(MeshAttribute::COLOR).unwrap().len(), 11);
+
+    merged.compute_flat_normals();
+    assert_eq!(merged.vertex_count(), 15);
+    assert_eq!(merged.validate(), Ok(()));
+    merged.compute_smooth_normals();
+    assert!(merged
+        .normals()
+        .unwrap()
+        .iter()
+        .all(|normal| Vec3::from(*normal).abs_diff_eq(Vec3::Z, 1e-5)));
+
+    let mut invalid = quad.clone();
+    invalid.indices.push(7);
+    assert_eq!(invalid.validate(), Err(MeshError::IncompleteTriangle(7)));
+    invalid.indices.extend([7, 0]);
+    assert_eq!(
+        invalid.validate(),
+        Err(MeshError::IndexOutOfRange {
+            index: 7,
+            vertices: 4
+        })
+    );
+    invalid.indices.truncate(6);
+    invalid.indices.extend([0, 1, 1]);
+    assert_eq!(invalid.validate(), Err(MeshError::DegenerateTriangle(2)));
+    assert_eq!(invalid.validate_drawable(), Ok(()));
+    if let Some(VertexAttributeValues::Float32x2(tex_coords)) =
+        invalid.attribute_mut(MeshAttribute::TEX_COORDS)
+    {
+        tex_coords[1][1] = f32::NAN;
+    }
+    assert_eq!(invalid.validate(), Err(MeshError::NonFinite(1)));
+    invalid.remove_attribute(MeshAttribute::TANGENT);
+    invalid.insert_attribute(MeshAttribute::COLOR, vec![[0.0; 4]; 3]);
+    assert_eq!(
+        invalid.validate(),
+        Err(MeshError::AttributeLength {
+            name: "color",
+            len: 3,
+            vertices: 4
+        })
+    );
+}
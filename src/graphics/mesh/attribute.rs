@@ -0,0 +1,218 @@
+use wgpu::VertexFormat;
+
+/// A named vertex attribute with the format it is stored in.
+///
+/// The name is what shaders call the attribute: [`VertexLayout::from_wgsl`] matches it with the
+/// fields of a vertex shader's input.
+///
+/// [`VertexLayout::from_wgsl`]: crate::render::vertex::VertexLayout::from_wgsl
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshAttribute {
+    pub name: &'static str,
+    pub format: VertexFormat,
+}
+
+impl MeshAttribute {
+    pub const POSITION: Self = Self::new("position", VertexFormat::Float32x3);
+    pub const NORMAL: Self = Self::new("normal", VertexFormat::Float32x3);
+    pub const TEX_COORDS: Self = Self::new("tex_coords", VertexFormat::Float32x2);
+    /// A second set of texture coordinates, e.g. for lightmaps.
+    pub const TEX_COORDS_1: Self = Self::new("tex_coords_1", VertexFormat::Float32x2);
+    /// The tangent in `xyz` and the handedness of the bitangent in `w`, see
+    /// [`Mesh::generate_tangents`](super::Mesh::generate_tangents).
+    pub const TANGENT: Self = Self::new("tangent", VertexFormat::Float32x4);
+    /// Linear RGBA.
+    pub const COLOR: Self = Self::new("color", VertexFormat::Float32x4);
+    pub const JOINT_INDICES: Self = Self::new("joint_indices", VertexFormat::Uint16x4);
+    pub const JOINT_WEIGHTS: Self = Self::new("joint_weights", VertexFormat::Float32x4);
+
+    /// The attributes that have a constant here.
+    pub const BUILTIN: [Self; 8] = [
+        Self::POSITION,
+        Self::NORMAL,
+        Self::TEX_COORDS,
+        Self::TEX_COORDS_1,
+        Self::TANGENT,
+        Self::COLOR,
+        Self::JOINT_INDICES,
+        Self::JOINT_WEIGHTS,
+    ];
+
+    pub const fn new(name: &'static str, format: VertexFormat) -> Self {
+        Self { name, format }
+    }
+}
+
+/// The values of one attribute for every vertex of a mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VertexAttributeValues {
+    Float32(Vec<f32>),
+    Float32x2(Vec<[f32; 2]>),
+    Float32x3(Vec<[f32; 3]>),
+    Float32x4(Vec<[f32; 4]>),
+    Uint32(Vec<u32>),
+    Uint32x4(Vec<[u32; 4]>),
+    Uint16x4(Vec<[u16; 4]>),
+    Unorm8x4(Vec<[u8; 4]>),
+}
+
+/// Evaluates `$body` with `$values` bound to the vector of any variant.
+macro_rules! with_values {
+    ($self:expr, $values:ident => $body:expr) => {
+        match $self {
+            VertexAttributeValues::Float32($values) => $body,
+            VertexAttributeValues::Float32x2($values) => $body,
+            VertexAttributeValues::Float32x3($values) => $body,
+            VertexAttributeValues::Float32x4($values) => $body,
+            VertexAttributeValues::Uint32($values) => $body,
+            VertexAttributeValues::Uint32x4($values) => $body,
+            VertexAttributeValues::Uint16x4($values) => $body,
+            VertexAttributeValues::Unorm8x4($values) => $body,
+        }
+    };
+}
+
+/// Like [`with_values!`], for a new vector of the same variant.
+macro_rules! map_values {
+    ($self:expr, $values:ident => $body:expr) => {
+        match $self {
+            VertexAttributeValues::Float32($values) => VertexAttributeValues::Float32($body),
+            VertexAttributeValues::Float32x2($values) => VertexAttributeValues::Float32x2($body),
+            VertexAttributeValues::Float32x3($values) => VertexAttributeValues::Float32x3($body),
+            VertexAttributeValues::Float32x4($values) => VertexAttributeValues::Float32x4($body),
+            VertexAttributeValues::Uint32($values) => VertexAttributeValues::Uint32($body),
+            VertexAttributeValues::Uint32x4($values) => VertexAttributeValues::Uint32x4($body),
+            VertexAttributeValues::Uint16x4($values) => VertexAttributeValues::Uint16x4($body),
+            VertexAttributeValues::Unorm8x4($values) => VertexAttributeValues::Unorm8x4($body),
+        }
+    };
+}
+
+impl VertexAttributeValues {
+    pub fn format(&self) -> VertexFormat {
+        match self {
+            Self::Float32(_) => VertexFormat::Float32,
+            Self::Float32x2(_) => VertexFormat::Float32x2,
+            Self::Float32x3(_) => VertexFormat::Float32x3,
+            Self::Float32x4(_) => VertexFormat::Float32x4,
+            Self::Uint32(_) => VertexFormat::Uint32,
+            Self::Uint32x4(_) => VertexFormat::Uint32x4,
+            Self::Uint16x4(_) => VertexFormat::Uint16x4,
+            Self::Unorm8x4(_) => VertexFormat::Unorm8x4,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        with_values!(self, values => values.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The values as tightly packed bytes, [`VertexFormat::size`] per vertex.
+    pub fn as_bytes(&self) -> &[u8] {
+        with_values!(self, values => bytemuck::cast_slice(values))
+    }
+
+    /// The components of floating-point attributes, or `None` for integer attributes.
+    pub fn as_floats(&self) -> Option<&[f32]> {
+        match self {
+            Self::Float32(values) => Some(values),
+            Self::Float32x2(values) => Some(bytemuck::cast_slice(values)),
+            Self::Float32x3(values) => Some(bytemuck::cast_slice(values)),
+            Self::Float32x4(values) => Some(bytemuck::cast_slice(values)),
+            _ => None,
+        }
+    }
+
+    pub fn as_float32x2(&self) -> Option<&[[f32; 2]]> {
+        match self {
+            Self::Float32x2(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_float32x3(&self) -> Option<&[[f32; 3]]> {
+        match self {
+            Self::Float32x3(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_float32x3_mut(&mut self) -> Option<&mut Vec<[f32; 3]>> {
+        match self {
+            Self::Float32x3(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_float32x4_mut(&mut self) -> Option<&mut Vec<[f32; 4]>> {
+        match self {
+            Self::Float32x4(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// The values at `indices`, in order and possibly repeated.
+    pub fn gather(&self, indices: impl IntoIterator<Item = usize>) -> Self {
+        map_values!(self, values => indices.into_iter().map(|index| values[index]).collect())
+    }
+
+    /// Appends the values of `other`, or zeros if it has a different format.
+    pub fn append(&mut self, other: &Self) {
+        let len = self.len() + other.len();
+        match (self, other) {
+            (Self::Float32(values), Self::Float32(other)) => values.extend(other),
+            (Self::Float32x2(values), Self::Float32x2(other)) => values.extend(other),
+            (Self::Float32x3(values), Self::Float32x3(other)) => values.extend(other),
+            (Self::Float32x4(values), Self::Float32x4(other)) => values.extend(other),
+            (Self::Uint32(values), Self::Uint32(other)) => values.extend(other),
+            (Self::Uint32x4(values), Self::Uint32x4(other)) => values.extend(other),
+            (Self::Uint16x4(values), Self::Uint16x4(other)) => values.extend(other),
+            (Self::Unorm8x4(values), Self::Unorm8x4(other)) => values.extend(other),
+            (values, _) => values.resize_zeroed(len),
+        }
+    }
+
+    /// Truncates the values or pads them with zeros.
+    pub fn resize_zeroed(&mut self, len: usize) {
+        with_values!(self, values => values.resize(len, bytemuck::Zeroable::zeroed()))
+    }
+
+    /// Empty values of `format`, which has to be one of the formats of the variants.
+    pub fn empty(format: VertexFormat) -> Self {
+        match format {
+            VertexFormat::Float32 => Self::Float32(Vec::new()),
+            VertexFormat::Float32x2 => Self::Float32x2(Vec::new()),
+            VertexFormat::Float32x3 => Self::Float32x3(Vec::new()),
+            VertexFormat::Float32x4 => Self::Float32x4(Vec::new()),
+            VertexFormat::Uint32 => Self::Uint32(Vec::new()),
+            VertexFormat::Uint32x4 => Self::Uint32x4(Vec::new()),
+            VertexFormat::Uint16x4 => Self::Uint16x4(Vec::new()),
+            VertexFormat::Unorm8x4 => Self::Unorm8x4(Vec::new()),
+            format => panic!("Unsupported vertex format {format:?}"),
+        }
+    }
+}
+
+macro_rules! impl_from_values {
+    ($($variant:ident($ty:ty)),* $(,)?) => {
+        $(impl From<Vec<$ty>> for VertexAttributeValues {
+            fn from(values: Vec<$ty>) -> Self {
+                Self::$variant(values)
+            }
+        })*
+    };
+}
+
+impl_from_values!(
+    Float32(f32),
+    Float32x2([f32; 2]),
+    Float32x3([f32; 3]),
+    Float32x4([f32; 4]),
+    Uint32(u32),
+    Uint32x4([u32; 4]),
+    Uint16x4([u16; 4]),
+    Unorm8x4([u8; 4]),
+);
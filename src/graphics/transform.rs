@@ -1,67 +1,224 @@
-use super::camera::CameraTransform;
+use glam::{Mat3, Mat4, Quat, Vec3};
 
-pub struct Transform(pub glam::Mat4);
+/// The translation, rotation and scale of an entity, i.e. its local-to-world transform.
+///
+/// Cameras use the same type as their [`CameraTransform`](super::camera::CameraTransform), where
+/// it is the camera-to-world transform and the camera looks along [`Transform::forward`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
 
 impl Transform {
-    pub fn borrow_matrix(&self) -> &glam::Mat4 {
-        &self.0
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3::ONE,
+    };
+
+    pub fn from_xyz(x: f32, y: f32, z: f32) -> Self {
+        Self::from_translation(Vec3::new(x, y, z))
     }
-}
 
-impl Default for Transform {
-    fn default() -> Self {
-        Self(glam::Mat4::IDENTITY)
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_rotation(rotation: Quat) -> Self {
+        Self {
+            rotation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_scale(scale: Vec3) -> Self {
+        Self {
+            scale,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Decomposes an affine matrix. Shear cannot be represented and is lost.
+    pub fn from_matrix(matrix: Mat4) -> Self {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn with_translation(mut self, translation: Vec3) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Turns the transform so that [`Transform::forward`] points at `target` and
+    /// [`Transform::up`] is as close to `up` as possible.
+    pub fn looking_at(mut self, target: Vec3, up: Vec3) -> Self {
+        self.look_at(target, up);
+        self
+    }
+
+    /// Like [`Transform::looking_at`], for a transform in place.
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) {
+        self.rotation = Quat::from_mat4(&Mat4::look_at_rh(self.translation, target, up).inverse());
+    }
+
+    /// Rotates by `rotation` in world space.
+    pub fn rotate(&mut self, rotation: Quat) {
+        self.rotation = (rotation * self.rotation).normalize();
+    }
+
+    /// Rotates around the world-space `axis` by `angle` radians.
+    pub fn rotate_axis(&mut self, axis: Vec3, angle: f32) {
+        self.rotate(Quat::from_axis_angle(axis.normalize(), angle));
+    }
+
+    /// Rotates around the `axis` in local space by `angle` radians.
+    pub fn rotate_local_axis(&mut self, axis: Vec3, angle: f32) {
+        self.rotation =
+            (self.rotation * Quat::from_axis_angle(axis.normalize(), angle)).normalize();
+    }
+
+    /// Moves by `offset` in world space.
+    pub fn translate(&mut self, offset: Vec3) {
+        self.translation += offset;
+    }
+
+    /// Moves by `offset` along the local axes, ignoring scale.
+    pub fn translate_local(&mut self, offset: Vec3) {
+        self.translation += self.rotation * offset;
+    }
+
+    /// The local negative z axis in world space.
+    pub fn forward(&self) -> Vec3 {
+        self.rotation * Vec3::NEG_Z
+    }
+
+    /// The local x axis in world space.
+    pub fn right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    /// The local y axis in world space.
+    pub fn up(&self) -> Vec3 {
+        self.rotation * Vec3::Y
+    }
+
+    pub fn compute_matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    /// The inverse transpose of the rotation and scale, which transforms normals so that they stay
+    /// perpendicular to surfaces under non-uniform scale. The results have to be renormalized.
+    pub fn compute_normal_matrix(&self) -> Mat3 {
+        Mat3::from_quat(self.rotation) * Mat3::from_diagonal(self.scale.recip())
+    }
+
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.translation + self.rotation * (self.scale * point)
     }
 }
 
-impl From<glam::Mat4> for Transform {
-    fn from(mat: glam::Mat4) -> Self {
-        Self(mat)
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
     }
 }
 
-impl From<Transform> for glam::Mat4 {
-    fn from(transform: Transform) -> glam::Mat4 {
-        transform.0
+impl From<Mat4> for Transform {
+    fn from(mat: Mat4) -> Self {
+        Self::from_matrix(mat)
     }
 }
 
-impl From<&Transform> for glam::Mat4 {
-    fn from(transform: &Transform) -> glam::Mat4 {
-        transform.0
+impl From<Transform> for Mat4 {
+    fn from(transform: Transform) -> Mat4 {
+        transform.compute_matrix()
     }
 }
 
-impl From<&glam::Mat4> for Transform {
-    fn from(mat: &glam::Mat4) -> Self {
-        Self(*mat)
+impl From<&Transform> for Mat4 {
+    fn from(transform: &Transform) -> Mat4 {
+        transform.compute_matrix()
     }
 }
 
-impl From<&CameraTransform> for Transform {
-    fn from(camera: &CameraTransform) -> Self {
-        Self(camera.0)
+impl From<&Mat4> for Transform {
+    fn from(mat: &Mat4) -> Self {
+        Self::from_matrix(*mat)
     }
 }
 
+/// The `ModelUniform` of the shaders.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
-pub struct Mat4Uniform {
+pub struct ModelUniform {
     model: [[f32; 4]; 4],
+    /// The [normal matrix](Transform::compute_normal_matrix), padded to a `mat4x4` to avoid the
+    /// column alignment of a `mat3x3`.
+    normal: [[f32; 4]; 4],
 }
 
-impl From<Transform> for Mat4Uniform {
+impl From<Transform> for ModelUniform {
     fn from(transform: Transform) -> Self {
-        Self {
-            model: transform.0.to_cols_array_2d(),
-        }
+        Self::from(&transform)
     }
 }
 
-impl From<&Transform> for Mat4Uniform {
+impl From<&Transform> for ModelUniform {
     fn from(transform: &Transform) -> Self {
         Self {
-            model: transform.0.to_cols_array_2d(),
+            model: transform.compute_matrix().to_cols_array_2d(),
+            normal: Mat4::from_mat3(transform.compute_normal_matrix()).to_cols_array_2d(),
         }
     }
 }
+
+#[test]
+fn test_transform() {
+    let mut transform = Transform::from_xyz(1.0, 2.0, 3.0).with_scale(Vec3::splat(2.0));
+    let matrix = transform.compute_matrix();
+    assert!(Transform::from(matrix)
+        .compute_matrix()
+        .abs_diff_eq(matrix, 1e-5));
+    assert!(transform
+        .transform_point(Vec3::X)
+        .abs_diff_eq(matrix.transform_point3(Vec3::X), 1e-5));
+
+    transform.look_at(Vec3::new(1.0, 2.0, 10.0), Vec3::Y);
+    assert!(transform.forward().abs_diff_eq(Vec3::Z, 1e-5));
+    assert!(transform.right().abs_diff_eq(Vec3::NEG_X, 1e-5));
+    transform.translate_local(Vec3::NEG_Z);
+    assert!(transform
+        .translation
+        .abs_diff_eq(Vec3::new(1.0, 2.0, 4.0), 1e-5));
+
+    let stretched = Transform::from_scale(Vec3::new(10.0, 1.0, 14.0))
+        .with_rotation(Quat::from_rotation_z(0.5))
+        .compute_matrix();
+    let normal_matrix = Transform::from(stretched).compute_normal_matrix();
+    let tangent = stretched.transform_vector3(Vec3::new(1.0, -1.0, 0.0));
+    let normal = normal_matrix * Vec3::new(1.0, 1.0, 0.0);
+    assert!(tangent.dot(normal).abs() < 1e-4);
+
+    transform.rotate_axis(Vec3::Y, std::f32::consts::PI);
+    assert!(transform.forward().abs_diff_eq(Vec3::NEG_Z, 1e-5));
+    assert!(transform.up().abs_diff_eq(Vec3::Y, 1e-5));
+}
@@ -1,37 +1,183 @@
 use std::collections::HashSet;
 
+use glam::Vec2;
 use winit::{
-    dpi::PhysicalPosition,
+    dpi::{LogicalPosition, PhysicalPosition, PhysicalSize},
     event::{MouseButton, MouseScrollDelta},
-    keyboard::Key,
+    keyboard::{Key, KeyCode, ModifiersState},
 };
 
+use crate::graphics::{camera::CameraTransform, ray::Ray, Projection};
+
+pub use button_input::ButtonInput;
+pub use input_map::{Action, Binding, InputMap};
+pub use text::TextInputEvent;
+pub use touch::{Touch, Touches};
+
+pub mod button_input;
+pub mod input_map;
+pub mod recording;
+pub mod text;
+pub mod touch;
+
+/// Resource holding the input received from the window.
+///
+/// Per-frame state (the `just_pressed`/`just_released` sets of the [`ButtonInput`]s, mouse motion,
+/// scrolling and text input) is cleared by [`InputState::clear`] at the end of every frame, after the
+/// [`Update`](crate::app::SystemStage::Update) systems and state transitions have run. Systems in
+/// [`FixedUpdate`](crate::app::SystemStage::FixedUpdate) may see a press in zero or several steps.
 pub struct InputState {
+    /// Held keys by the character or named key they produce, which depends on layout and
+    /// modifiers.
     pub keys: HashSet<Key>,
+    /// Keys by their physical location on the keyboard.
+    pub key_codes: ButtonInput<KeyCode>,
+    /// The modifier keys currently held.
+    pub modifiers: ModifiersState,
+    /// Text typed and IME events received this frame.
+    pub text_input: Vec<TextInputEvent>,
+    /// The cursor position in physical pixels relative to the top-left of the window.
     pub mouse_position: PhysicalPosition<f64>,
+    /// The cursor position before the most recent cursor movement.
     pub last_mouse_position: Option<PhysicalPosition<f64>>,
+    /// Whether the cursor is inside the window.
+    pub cursor_in_window: bool,
+    /// The size of the window's client area in physical pixels.
+    pub window_size: PhysicalSize<u32>,
+    /// The window's scale factor, used to convert between physical and logical pixels.
+    pub scale_factor: f64,
     pub mouse_delta: (f64, f64),
     pub mouse_scroll_delta: MouseScrollDelta,
-    pub mouse_buttons: HashSet<MouseButton>,
-    pub clicked: bool,
+    pub mouse_buttons: ButtonInput<MouseButton>,
 }
 
 impl InputState {
+    /// Pixels of smooth scrolling, e.g. on a trackpad, that count as one line of a scroll wheel.
+    pub const PIXELS_PER_LINE: f32 = 20.0;
+
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Vertical scrolling this frame in lines, with pixel deltas converted at
+    /// [`InputState::PIXELS_PER_LINE`].
+    pub fn scroll_lines(&self) -> f32 {
+        match self.mouse_scroll_delta {
+            MouseScrollDelta::LineDelta(_, y) => y,
+            MouseScrollDelta::PixelDelta(position) => position.y as f32 / Self::PIXELS_PER_LINE,
+        }
+    }
+
+    /// The cursor position in logical pixels relative to the top-left of the window.
+    pub fn cursor_logical_position(&self) -> LogicalPosition<f64> {
+        self.mouse_position.to_logical(self.scale_factor)
+    }
+
+    /// The cursor position in normalized device coordinates, from `(-1, -1)` at the bottom-left
+    /// of the window to `(1, 1)` at the top-right.
+    ///
+    /// Returns `None` while the cursor is outside the window.
+    pub fn cursor_ndc(&self) -> Option<Vec2> {
+        if !self.cursor_in_window || self.window_size.width == 0 || self.window_size.height == 0 {
+            return None;
+        }
+        let x = self.mouse_position.x / self.window_size.width as f64;
+        let y = self.mouse_position.y / self.window_size.height as f64;
+        Some(Vec2::new((2.0 * x - 1.0) as f32, (1.0 - 2.0 * y) as f32))
+    }
+
+    /// The world-space ray through the cursor for the given camera, for mouse picking.
+    ///
+    /// Returns `None` while the cursor is outside the window.
+    pub fn cursor_ray(
+        &self,
+        camera: &Projection,
+        camera_transform: &CameraTransform,
+    ) -> Option<Ray> {
+        self.cursor_ndc()
+            .map(|ndc| camera.ndc_to_ray(camera_transform, ndc))
+    }
+
+    /// The text typed or committed through the IME this frame.
+    pub fn typed_text(&self) -> String {
+        self.text_input
+            .iter()
+            .filter_map(|event| match event {
+                TextInputEvent::Text(text) | TextInputEvent::Commit(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Clears the state that only lasts for a single frame.
+    pub fn clear(&mut self) {
+        self.key_codes.clear();
+        self.text_input.clear();
+        self.mouse_buttons.clear();
+        self.mouse_delta = (0.0, 0.0);
+        self.mouse_scroll_delta = MouseScrollDelta::LineDelta(0.0, 0.0);
+    }
+
+    /// Releases all keys and mouse buttons, e.g. when the window loses focus and would not
+    /// receive their release events.
+    pub fn release_all(&mut self) {
+        self.keys.clear();
+        self.key_codes.release_all();
+        self.modifiers = ModifiersState::empty();
+        self.mouse_buttons.release_all();
+    }
 }
 
 impl Default for InputState {
     fn default() -> Self {
         Self {
             keys: HashSet::new(),
+            key_codes: ButtonInput::default(),
+            modifiers: ModifiersState::empty(),
+            text_input: Vec::new(),
             mouse_position: PhysicalPosition::new(0.0, 0.0),
             last_mouse_position: None,
+            cursor_in_window: false,
+            window_size: PhysicalSize::new(0, 0),
+            scale_factor: 1.0,
             mouse_delta: (0.0, 0.0),
             mouse_scroll_delta: MouseScrollDelta::LineDelta(0.0, 0.0),
-            mouse_buttons: HashSet::new(),
-            clicked: false,
+            mouse_buttons: ButtonInput::default(),
         }
     }
 }
+
+#[test]
+fn test_cursor_ndc() {
+    let mut input = InputState {
+        window_size: PhysicalSize::new(800, 600),
+        cursor_in_window: true,
+        ..Default::default()
+    };
+    input.mouse_position = PhysicalPosition::new(0.0, 0.0);
+    assert_eq!(input.cursor_ndc(), Some(Vec2::new(-1.0, 1.0)));
+    input.mouse_position = PhysicalPosition::new(400.0, 300.0);
+    assert_eq!(input.cursor_ndc(), Some(Vec2::ZERO));
+    input.cursor_in_window = false;
+    assert_eq!(input.cursor_ndc(), None);
+}
+
+#[test]
+fn test_text_input() {
+    let mut input = InputState::new();
+    input.text_input.push(TextInputEvent::Text("a".into()));
+    assert_eq!(TextInputEvent::from_key_text("\u{8}"), None);
+    assert_eq!(TextInputEvent::from_key_text("\r"), None);
+    assert_eq!(
+        TextInputEvent::from_key_text("b\u{7f}"),
+        Some(TextInputEvent::Text("b".into()))
+    );
+    input.text_input.push(TextInputEvent::Preedit {
+        text: "b".into(),
+        cursor: Some((1, 1)),
+    });
+    input.text_input.push(TextInputEvent::Commit("漢字".into()));
+    assert_eq!(input.typed_text(), "a漢字");
+    input.clear();
+    assert_eq!(input.typed_text(), "");
+}
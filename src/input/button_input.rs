@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Tracks which buttons of type `T` are held, and which were pressed or released this frame.
+///
+/// Used in [`InputState`](super::InputState) for keys by [`KeyCode`](winit::keyboard::KeyCode)
+/// and for [`MouseButton`](winit::event::MouseButton)s.
+#[derive(Debug, Clone)]
+pub struct ButtonInput<T: Copy + Eq + Hash> {
+    pressed: HashSet<T>,
+    just_pressed: HashSet<T>,
+    just_released: HashSet<T>,
+}
+
+impl<T: Copy + Eq + Hash> Default for ButtonInput<T> {
+    fn default() -> Self {
+        Self {
+            pressed: Default::default(),
+            just_pressed: Default::default(),
+            just_released: Default::default(),
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> ButtonInput<T> {
+    /// Registers a press of `button`. Repeated presses while held are ignored.
+    pub fn press(&mut self, button: T) {
+        if self.pressed.insert(button) {
+            self.just_pressed.insert(button);
+        }
+    }
+
+    /// Registers a release of `button`.
+    pub fn release(&mut self, button: T) {
+        if self.pressed.remove(&button) {
+            self.just_released.insert(button);
+        }
+    }
+
+    /// Releases every held button, e.g. when the window loses focus and release events would be
+    /// missed.
+    pub fn release_all(&mut self) {
+        self.just_released.extend(self.pressed.drain());
+    }
+
+    /// Whether `button` is held.
+    pub fn pressed(&self, button: T) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    /// Whether any of `buttons` is held.
+    pub fn any_pressed(&self, buttons: impl IntoIterator<Item = T>) -> bool {
+        buttons.into_iter().any(|button| self.pressed(button))
+    }
+
+    /// Whether `button` was pressed this frame.
+    pub fn just_pressed(&self, button: T) -> bool {
+        self.just_pressed.contains(&button)
+    }
+
+    /// Whether any of `buttons` was pressed this frame.
+    pub fn any_just_pressed(&self, buttons: impl IntoIterator<Item = T>) -> bool {
+        buttons.into_iter().any(|button| self.just_pressed(button))
+    }
+
+    /// Whether `button` was released this frame.
+    pub fn just_released(&self, button: T) -> bool {
+        self.just_released.contains(&button)
+    }
+
+    pub fn get_pressed(&self) -> impl Iterator<Item = &T> {
+        self.pressed.iter()
+    }
+
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = &T> {
+        self.just_pressed.iter()
+    }
+
+    pub fn get_just_released(&self) -> impl Iterator<Item = &T> {
+        self.just_released.iter()
+    }
+
+    /// Forgets this frame's presses and releases while keeping held buttons held.
+    pub fn clear(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+
+    /// Forgets all state, including held buttons.
+    pub fn reset_all(&mut self) {
+        self.pressed.clear();
+        self.clear();
+    }
+}
+
+#[test]
+fn test_button_input() {
+    let mut input = ButtonInput::<u32>::default();
+
+    input.press(1);
+    input.press(1);
+    assert!(input.pressed(1));
+    assert!(input.just_pressed(1));
+
+    input.clear();
+    assert!(input.pressed(1));
+    assert!(!input.just_pressed(1));
+
+    input.press(2);
+    input.release_all();
+    assert!(!input.pressed(1));
+    assert!(input.just_released(1));
+    assert!(input.just_pressed(2) && input.just_released(2));
+}
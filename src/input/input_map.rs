@@ -0,0 +1,210 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+use super::InputState;
+use crate::ecs::resource::{Res, ResMut};
+
+/// Types that can be used as actions in an [`InputMap`].
+///
+/// This is implemented for every `Copy + Eq + Hash` type, so a plain enum is enough.
+pub trait Action: Copy + Eq + Hash + 'static {}
+
+impl<T: Copy + Eq + Hash + 'static> Action for T {}
+
+/// A physical input that an action can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binding {
+    /// A key, identified by its physical location so that it is unaffected by modifiers and
+    /// keyboard layout.
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// An axis that is `-1` while `negative` is held and `1` while `positive` is held.
+    KeyAxis {
+        negative: KeyCode,
+        positive: KeyCode,
+    },
+    /// Horizontal mouse motion this frame, in raw device units.
+    MouseMotionX,
+    /// Vertical mouse motion this frame, in raw device units.
+    MouseMotionY,
+    /// Vertical scrolling this frame in lines, see [`InputState::scroll_lines`].
+    MouseWheel,
+}
+
+impl Binding {
+    /// The value of the binding given the current input state. Buttons are `1` while held and `0`
+    /// otherwise.
+    pub fn value(&self, input: &InputState) -> f32 {
+        let held = |key: &KeyCode| input.key_codes.pressed(*key) as i32 as f32;
+        match self {
+            Binding::Key(key) => held(key),
+            Binding::Mouse(button) => input.mouse_buttons.pressed(*button) as i32 as f32,
+            Binding::KeyAxis { negative, positive } => held(positive) - held(negative),
+            Binding::MouseMotionX => input.mouse_delta.0 as f32,
+            Binding::MouseMotionY => input.mouse_delta.1 as f32,
+            Binding::MouseWheel => input.scroll_lines(),
+        }
+    }
+}
+
+impl From<KeyCode> for Binding {
+    fn from(key: KeyCode) -> Self {
+        Binding::Key(key)
+    }
+}
+
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Self {
+        Binding::Mouse(button)
+    }
+}
+
+/// Resource mapping user-defined actions to physical inputs.
+///
+/// Add it to an app with [`App::with_input_map`](crate::app::App::with_input_map), which also
+/// updates it at the start of every frame. Bindings can be changed at any time with
+/// [`InputMap::bind`] and [`InputMap::unbind`].
+pub struct InputMap<A: Action> {
+    bindings: HashMap<A, Vec<Binding>>,
+    values: HashMap<A, f32>,
+    pressed: HashSet<A>,
+    previously_pressed: HashSet<A>,
+}
+
+impl<A: Action> Default for InputMap<A> {
+    fn default() -> Self {
+        Self {
+            bindings: Default::default(),
+            values: Default::default(),
+            pressed: Default::default(),
+            previously_pressed: Default::default(),
+        }
+    }
+}
+
+impl<A: Action> InputMap<A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a binding to `action`.
+    pub fn with_binding(mut self, action: A, binding: impl Into<Binding>) -> Self {
+        self.bind(action, binding);
+        self
+    }
+
+    /// Adds a binding to `action`. An action can have any number of bindings, whose values are
+    /// summed.
+    pub fn bind(&mut self, action: A, binding: impl Into<Binding>) {
+        self.bindings
+            .entry(action)
+            .or_default()
+            .push(binding.into());
+    }
+
+    /// Removes a binding from `action`.
+    pub fn unbind(&mut self, action: A, binding: impl Into<Binding>) {
+        let binding = binding.into();
+        if let Some(bindings) = self.bindings.get_mut(&action) {
+            bindings.retain(|b| *b != binding);
+        }
+    }
+
+    /// Removes all bindings from `action`.
+    pub fn clear_bindings(&mut self, action: A) {
+        self.bindings.remove(&action);
+    }
+
+    pub fn bindings(&self, action: A) -> &[Binding] {
+        self.bindings
+            .get(&action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Whether any of the action's bindings is active this frame.
+    pub fn pressed(&self, action: A) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    /// Whether the action became active this frame.
+    pub fn just_pressed(&self, action: A) -> bool {
+        self.pressed.contains(&action) && !self.previously_pressed.contains(&action)
+    }
+
+    /// Whether the action stopped being active this frame.
+    pub fn just_released(&self, action: A) -> bool {
+        !self.pressed.contains(&action) && self.previously_pressed.contains(&action)
+    }
+
+    /// The sum of the values of the action's bindings this frame.
+    pub fn axis_value(&self, action: A) -> f32 {
+        self.values.get(&action).copied().unwrap_or_default()
+    }
+
+    /// Recomputes the state of every action from `input`.
+    pub fn update(&mut self, input: &InputState) {
+        self.previously_pressed = std::mem::take(&mut self.pressed);
+        self.values.clear();
+        for (action, bindings) in &self.bindings {
+            let value: f32 = bindings.iter().map(|binding| binding.value(input)).sum();
+            self.values.insert(*action, value);
+            if value != 0.0 {
+                self.pressed.insert(*action);
+            }
+        }
+    }
+}
+
+pub fn update_input_map_system<A: Action>(
+    input: Res<InputState>,
+    mut input_map: ResMut<InputMap<A>>,
+) {
+    input_map.update(&input);
+}
+
+#[test]
+fn test_input_map() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum TestAction {
+        Jump,
+        Move,
+    }
+
+    let mut input_map = InputMap::new()
+        .with_binding(TestAction::Jump, KeyCode::Space)
+        .with_binding(
+            TestAction::Move,
+            Binding::KeyAxis {
+                negative: KeyCode::KeyA,
+                positive: KeyCode::KeyD,
+            },
+        );
+    let mut input = InputState::default();
+
+    input.key_codes.press(KeyCode::Space);
+    input.key_codes.press(KeyCode::KeyA);
+    input_map.update(&input);
+    assert!(input_map.just_pressed(TestAction::Jump));
+    assert_eq!(input_map.axis_value(TestAction::Move), -1.0);
+
+    input_map.update(&input);
+    assert!(input_map.pressed(TestAction::Jump));
+    assert!(!input_map.just_pressed(TestAction::Jump));
+
+    input.key_codes.reset_all();
+    input_map.unbind(TestAction::Jump, KeyCode::Space);
+    input_map.bind(TestAction::Jump, MouseButton::Left);
+    input.mouse_buttons.press(MouseButton::Left);
+    input_map.update(&input);
+    assert!(input_map.pressed(TestAction::Jump));
+    assert!(input_map.just_released(TestAction::Move));
+
+    // Smooth scrolling is measured in lines like a scroll wheel
+    input.mouse_scroll_delta = winit::event::MouseScrollDelta::PixelDelta(
+        winit::dpi::PhysicalPosition::new(0.0, 2.0 * InputState::PIXELS_PER_LINE as f64),
+    );
+    assert_eq!(Binding::MouseWheel.value(&input), 2.0);
+}
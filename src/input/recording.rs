@@ -0,0 +1,437 @@
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{MouseButton, MouseScrollDelta, TouchPhase},
+    keyboard::{Key, KeyCode, ModifiersState},
+};
+
+use super::{InputState, TextInputEvent, Touches};
+use crate::ecs::World;
+use crate::graphics::Transform;
+
+/// Written at the start of every recording file.
+const MAGIC: &[u8; 8] = b"CARNOTIR";
+/// Incremented whenever the layout of [`RecordedFrame`] changes.
+const VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum RecordingError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Encoding error: {0}")]
+    Encoding(#[from] bincode::Error),
+    #[error("Not an input recording")]
+    InvalidHeader,
+    #[error("Unsupported recording version {0}, expected {VERSION}")]
+    UnsupportedVersion(u32),
+}
+
+/// A single mutation of the [`InputState`] or [`Touches`].
+///
+/// All window and device input goes through [`InputEvent::apply`], so that it can be recorded
+/// with an [`InputRecorder`] and played back with an [`InputReplay`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Key {
+        logical_key: Key,
+        key_code: Option<KeyCode>,
+        pressed: bool,
+    },
+    ModifiersChanged(ModifiersState),
+    Text(TextInputEvent),
+    MouseButton {
+        button: MouseButton,
+        pressed: bool,
+    },
+    MouseMotion {
+        delta: (f64, f64),
+    },
+    MouseWheel {
+        delta: MouseScrollDelta,
+    },
+    CursorMoved {
+        position: PhysicalPosition<f64>,
+    },
+    CursorEntered,
+    CursorLeft,
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        position: PhysicalPosition<f64>,
+    },
+    Resized(PhysicalSize<u32>),
+    ScaleFactorChanged(f64),
+    /// The window lost focus.
+    ReleaseAll,
+}
+
+impl InputEvent {
+    pub fn apply(&self, input: &mut InputState, touches: &mut Touches) {
+        match self {
+            InputEvent::Key {
+                logical_key,
+                key_code,
+                pressed: true,
+            } => {
+                input.keys.insert(logical_key.clone());
+                if let Some(key_code) = key_code {
+                    input.key_codes.press(*key_code);
+                }
+            }
+            InputEvent::Key {
+                logical_key,
+                key_code,
+                pressed: false,
+            } => {
+                input.keys.remove(logical_key);
+                if let Some(key_code) = key_code {
+                    input.key_codes.release(*key_code);
+                }
+            }
+            InputEvent::ModifiersChanged(modifiers) => input.modifiers = *modifiers,
+            InputEvent::Text(event) => input.text_input.push(event.clone()),
+            InputEvent::MouseButton {
+                button,
+                pressed: true,
+            } => input.mouse_buttons.press(*button),
+            InputEvent::MouseButton {
+                button,
+                pressed: false,
+            } => input.mouse_buttons.release(*button),
+            InputEvent::MouseMotion { delta } => {
+                input.mouse_delta = (input.mouse_delta.0 + delta.0, input.mouse_delta.1 + delta.1);
+            }
+            InputEvent::MouseWheel { delta } => input.mouse_scroll_delta = *delta,
+            InputEvent::CursorMoved { position } => {
+                input.last_mouse_position = Some(input.mouse_position);
+                input.mouse_position = *position;
+                input.cursor_in_window = true;
+            }
+            InputEvent::CursorEntered => input.cursor_in_window = true,
+            InputEvent::CursorLeft => input.cursor_in_window = false,
+            InputEvent::Touch {
+                id,
+                phase,
+                position,
+            } => {
+                let primary = touches.process(*id, *phase, *position);
+                if touches.emulate_mouse && primary {
+                    emulate_mouse(input, *phase, *position);
+                }
+            }
+            InputEvent::Resized(size) => input.window_size = *size,
+            InputEvent::ScaleFactorChanged(scale_factor) => input.scale_factor = *scale_factor,
+            InputEvent::ReleaseAll => {
+                input.release_all();
+                touches.release_all();
+            }
+        }
+    }
+}
+
+/// Drives the cursor, mouse delta and left mouse button from the primary touch.
+fn emulate_mouse(input: &mut InputState, phase: TouchPhase, position: PhysicalPosition<f64>) {
+    if phase != TouchPhase::Started {
+        let delta = (
+            position.x - input.mouse_position.x,
+            position.y - input.mouse_position.y,
+        );
+        input.mouse_delta = (input.mouse_delta.0 + delta.0, input.mouse_delta.1 + delta.1);
+    }
+    input.last_mouse_position = Some(input.mouse_position);
+    input.mouse_position = position;
+    input.cursor_in_window = true;
+    match phase {
+        TouchPhase::Started => input.mouse_buttons.press(MouseButton::Left),
+        TouchPhase::Moved => {}
+        TouchPhase::Ended | TouchPhase::Cancelled => input.mouse_buttons.release(MouseButton::Left),
+    }
+}
+
+/// The input of a single frame.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// The wall time since the previous frame, which drives the
+    /// [`FixedTime`](crate::builtins::resources::time::FixedTime) accumulator.
+    pub delta: Duration,
+    pub events: Vec<InputEvent>,
+    /// The combined checksum of the chosen components at the end of the frame.
+    pub checksum: u64,
+}
+
+/// Components that can be included in the per-frame checksum of a recording.
+pub trait Checksum: 'static {
+    fn checksum(&self, hasher: &mut dyn Hasher);
+}
+
+impl Checksum for Transform {
+    fn checksum(&self, hasher: &mut dyn Hasher) {
+        let Transform {
+            translation,
+            rotation,
+            scale,
+        } = self;
+        for value in [translation.to_array(), scale.to_array()]
+            .concat()
+            .into_iter()
+            .chain(rotation.to_array())
+        {
+            hasher.write_u32(value.to_bits());
+        }
+    }
+}
+
+type ChecksumFn = fn(&World, &mut dyn Hasher);
+
+fn hash_components<T: Checksum>(world: &World, hasher: &mut dyn Hasher) {
+    let Some(components) = world.borrow_component_vec::<T>() else {
+        return;
+    };
+    for (entity, component) in components.iter().enumerate() {
+        if let Some(component) = component {
+            hasher.write_u64(entity as u64);
+            component.checksum(hasher);
+        }
+    }
+}
+
+/// FNV-1a, used instead of the standard library's hasher so that checksums are stable across
+/// builds. Integers are hashed as little-endian bytes so that they are stable across platforms
+/// too.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+}
+
+fn world_checksum(checksums: &[ChecksumFn], world: &World) -> u64 {
+    let mut hasher = Fnv1a::default();
+    for checksum in checksums {
+        checksum(world, &mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Resource that records all input, frame by frame, and saves it to a file when the app exits.
+///
+/// The frames are kept in memory until then, so nothing is saved if the app crashes or is
+/// killed. Call [`InputRecorder::save`] from a system to write the frames recorded so far, e.g.
+/// every few seconds.
+///
+/// Add it with [`App::with_input_recording`](crate::app::App::with_input_recording).
+pub struct InputRecorder {
+    path: PathBuf,
+    frames: Vec<RecordedFrame>,
+    current: Vec<InputEvent>,
+    checksums: Vec<ChecksumFn>,
+}
+
+impl InputRecorder {
+    /// Creates a recorder that saves to `path`.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            frames: Vec::new(),
+            current: Vec::new(),
+            checksums: Vec::new(),
+        }
+    }
+
+    /// Includes every `T` component in the per-frame checksum.
+    pub fn with_checksum<T: Checksum>(mut self) -> Self {
+        self.checksums.push(hash_components::<T>);
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// Records an event in the current frame.
+    pub fn record(&mut self, event: InputEvent) {
+        self.current.push(event);
+    }
+
+    /// Finishes the current frame.
+    pub fn end_frame(&mut self, delta: Duration, world: &World) {
+        self.frames.push(RecordedFrame {
+            delta,
+            events: std::mem::take(&mut self.current),
+            checksum: world_checksum(&self.checksums, world),
+        });
+    }
+
+    /// Writes the recorded frames to the recorder's path.
+    pub fn save(&self) -> Result<(), RecordingError> {
+        let mut writer = BufWriter::new(File::create(&self.path)?);
+        write_frames(&mut writer, &self.frames)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+fn write_frames(writer: &mut impl Write, frames: &[RecordedFrame]) -> Result<(), RecordingError> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    bincode::serialize_into(writer, frames)?;
+    Ok(())
+}
+
+fn read_frames(reader: &mut impl Read) -> Result<Vec<RecordedFrame>, RecordingError> {
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(RecordingError::InvalidHeader);
+    }
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != VERSION {
+        return Err(RecordingError::UnsupportedVersion(version));
+    }
+    Ok(bincode::deserialize_from(reader)?)
+}
+
+/// Resource that plays back a recording made with an [`InputRecorder`].
+///
+/// While it is present, live input is ignored and every frame applies the next recorded frame's
+/// events and time step instead. Once every frame has been played, the app exits with code `0`,
+/// or `1` if any frame's checksum differed from the recording.
+///
+/// Add it with [`App::with_input_replay`](crate::app::App::with_input_replay).
+pub struct InputReplay {
+    frames: Vec<RecordedFrame>,
+    next: usize,
+    checksums: Vec<ChecksumFn>,
+    divergence: Option<usize>,
+}
+
+impl InputReplay {
+    pub fn new(frames: Vec<RecordedFrame>) -> Self {
+        Self {
+            frames,
+            next: 0,
+            checksums: Vec::new(),
+            divergence: None,
+        }
+    }
+
+    /// Loads a recording saved by [`InputRecorder::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        Ok(Self::new(read_frames(&mut reader)?))
+    }
+
+    /// Includes every `T` component in the per-frame checksum. This should match the checksums
+    /// the recording was made with.
+    pub fn with_checksum<T: Checksum>(mut self) -> Self {
+        self.checksums.push(hash_components::<T>);
+        self
+    }
+
+    /// Whether every frame has been played.
+    pub fn finished(&self) -> bool {
+        self.next >= self.frames.len()
+    }
+
+    /// The first frame whose checksum differed from the recording, if any.
+    pub fn divergence(&self) -> Option<usize> {
+        self.divergence
+    }
+
+    /// Applies the next frame's events to `input` and `touches`, returning the frame's time step.
+    pub fn begin_frame(
+        &mut self,
+        input: &mut InputState,
+        touches: &mut Touches,
+    ) -> Option<Duration> {
+        let frame = self.frames.get(self.next)?;
+        for event in &frame.events {
+            event.apply(input, touches);
+        }
+        Some(frame.delta)
+    }
+
+    /// Compares the world against the recorded checksum of the current frame and moves on to the
+    /// next frame.
+    pub fn end_frame(&mut self, world: &World) {
+        let Some(frame) = self.frames.get(self.next) else {
+            return;
+        };
+        if self.divergence.is_none() && world_checksum(&self.checksums, world) != frame.checksum {
+            eprintln!("Replay diverged from the recording at frame {}", self.next);
+            self.divergence = Some(self.next);
+        }
+        self.next += 1;
+    }
+}
+
+#[test]
+fn test_recording_round_trip() {
+    let mut world = World::new();
+    let entity = world.new_entity();
+    world.add_component_to_entity(entity, Transform::default());
+
+    let mut recorder = InputRecorder::new("unused").with_checksum::<Transform>();
+    recorder.record(InputEvent::Key {
+        logical_key: Key::Character("w".into()),
+        key_code: Some(KeyCode::KeyW),
+        pressed: true,
+    });
+    recorder.record(InputEvent::MouseMotion { delta: (3.0, -1.0) });
+    recorder.end_frame(Duration::from_millis(16), &world);
+
+    let mut bytes = Vec::new();
+    write_frames(&mut bytes, recorder.frames()).unwrap();
+    let frames = read_frames(&mut bytes.as_slice()).unwrap();
+    assert_eq!(frames[0].events, recorder.frames()[0].events);
+
+    let mut replay = InputReplay::new(frames).with_checksum::<Transform>();
+    let mut input = InputState::default();
+    assert_eq!(
+        replay.begin_frame(&mut input, &mut Touches::default()),
+        Some(Duration::from_millis(16))
+    );
+    assert!(input.key_codes.just_pressed(KeyCode::KeyW));
+    assert_eq!(input.mouse_delta, (3.0, -1.0));
+
+    world.borrow_component_vec_mut::<Transform>().unwrap()[entity] =
+        Some(Transform::from_translation(glam::Vec3::X));
+    replay.end_frame(&world);
+    assert_eq!(replay.divergence(), Some(0));
+    assert!(replay.finished());
+}
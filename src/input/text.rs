@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use winit::event::Ime;
+
+/// A piece of text input: typed text or a change in IME composition.
+///
+/// The events of the current frame are collected in the [`InputState`](super::InputState), and
+/// [`InputState::typed_text`](super::InputState::typed_text) joins the text they insert. Use them
+/// rather than [`InputState::keys`](super::InputState::keys) for chat boxes, consoles and other
+/// text fields, since they account for key repeat, dead keys and input methods.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextInputEvent {
+    /// Text produced by a key press, including repeats while the key is held. Control
+    /// characters such as backspace, delete and return are left out, since they arrive as key
+    /// events.
+    Text(String),
+    /// The IME was enabled. Until it is disabled, composed text arrives through
+    /// [`TextInputEvent::Preedit`] and [`TextInputEvent::Commit`].
+    ImeEnabled,
+    /// The text currently being composed with the IME, which should be displayed in place of the
+    /// cursor but not yet inserted. An empty `text` clears it.
+    Preedit {
+        text: String,
+        /// The byte range of the IME cursor in `text`, or `None` if it should be hidden.
+        cursor: Option<(usize, usize)>,
+    },
+    /// Text that finished composing with the IME and should be inserted.
+    Commit(String),
+    /// The IME was disabled.
+    ImeDisabled,
+}
+
+impl TextInputEvent {
+    /// The [`TextInputEvent::Text`] for the text of a key press, or `None` if it only consists
+    /// of control characters.
+    pub fn from_key_text(text: &str) -> Option<Self> {
+        let text = text.chars().filter(|c| !c.is_control()).collect::<String>();
+        (!text.is_empty()).then_some(Self::Text(text))
+    }
+}
+
+impl From<Ime> for TextInputEvent {
+    fn from(ime: Ime) -> Self {
+        match ime {
+            Ime::Enabled => TextInputEvent::ImeEnabled,
+            Ime::Preedit(text, cursor) => TextInputEvent::Preedit { text, cursor },
+            Ime::Commit(text) => TextInputEvent::Commit(text),
+            Ime::Disabled => TextInputEvent::ImeDisabled,
+        }
+    }
+}
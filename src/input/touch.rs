@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+use winit::{dpi::PhysicalPosition, event::TouchPhase};
+
+/// A finger or stylus on a touchscreen.
+///
+/// Positions are in physical pixels relative to the top-left of the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    pub id: u64,
+    pub start_position: Vec2,
+    pub position: Vec2,
+    /// The position at the start of the frame.
+    pub previous_position: Vec2,
+    pub phase: TouchPhase,
+}
+
+impl Touch {
+    /// How far the touch moved this frame.
+    pub fn delta(&self) -> Vec2 {
+        self.position - self.previous_position
+    }
+
+    /// How far the touch moved since it started.
+    pub fn distance(&self) -> Vec2 {
+        self.position - self.start_position
+    }
+}
+
+/// Resource tracking the touches on a touchscreen, by the id winit assigns to each.
+///
+/// Like [`InputState`](super::InputState), the per-frame state is cleared with
+/// [`Touches::clear`] at the end of every frame.
+#[derive(Debug, Clone, Default)]
+pub struct Touches {
+    active: HashMap<u64, Touch>,
+    just_started: Vec<u64>,
+    just_ended: Vec<Touch>,
+    primary: Option<u64>,
+    /// Whether the primary touch, i.e. the first finger down, also drives the mouse: it moves the
+    /// cursor, adds to the mouse delta and holds the left mouse button.
+    pub emulate_mouse: bool,
+}
+
+impl Touches {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Touch> {
+        self.active.get(&id)
+    }
+
+    /// The touches currently on the screen.
+    pub fn iter(&self) -> impl Iterator<Item = &Touch> {
+        self.active.values()
+    }
+
+    /// The number of touches currently on the screen.
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Whether the touch started this frame.
+    pub fn just_started(&self, id: u64) -> bool {
+        self.just_started.contains(&id)
+    }
+
+    /// The touches that started this frame.
+    pub fn iter_just_started(&self) -> impl Iterator<Item = &Touch> {
+        self.just_started
+            .iter()
+            .filter_map(|id| self.active.get(id))
+    }
+
+    /// The touches that ended or were cancelled this frame, with their final positions.
+    pub fn iter_just_ended(&self) -> impl Iterator<Item = &Touch> {
+        self.just_ended.iter()
+    }
+
+    /// The first touch that is still on the screen, if any.
+    pub fn primary(&self) -> Option<&Touch> {
+        self.primary.and_then(|id| self.active.get(&id))
+    }
+
+    /// The two touches of a two-finger gesture, ordered by id. Returns `None` unless exactly two
+    /// touches are on the screen.
+    fn pair(&self) -> Option<(&Touch, &Touch)> {
+        if self.active.len() != 2 {
+            return None;
+        }
+        let mut touches = self.active.values();
+        let (a, b) = (touches.next()?, touches.next()?);
+        Some(if a.id < b.id { (a, b) } else { (b, a) })
+    }
+
+    /// The change in distance between two fingers this frame, as a ratio: above `1` when they
+    /// spread apart and below `1` when they pinch together.
+    ///
+    /// Returns `None` unless exactly two touches are on the screen.
+    pub fn pinch(&self) -> Option<f32> {
+        let (a, b) = self.pair()?;
+        let previous = a.previous_position.distance(b.previous_position);
+        let current = a.position.distance(b.position);
+        if previous <= f32::EPSILON {
+            return Some(1.0);
+        }
+        Some(current / previous)
+    }
+
+    /// How far the midpoint between two fingers moved this frame.
+    ///
+    /// Returns `None` unless exactly two touches are on the screen.
+    pub fn two_finger_drag(&self) -> Option<Vec2> {
+        let (a, b) = self.pair()?;
+        Some((a.delta() + b.delta()) / 2.0)
+    }
+
+    /// Updates the touch `id`, returning whether it is the primary touch.
+    pub fn process(&mut self, id: u64, phase: TouchPhase, position: PhysicalPosition<f64>) -> bool {
+        let position = Vec2::new(position.x as f32, position.y as f32);
+        match phase {
+            TouchPhase::Started => {
+                self.active.insert(
+                    id,
+                    Touch {
+                        id,
+                        start_position: position,
+                        position,
+                        previous_position: position,
+                        phase,
+                    },
+                );
+                self.just_started.push(id);
+                if self.primary.is_none() {
+                    self.primary = Some(id);
+                }
+            }
+            TouchPhase::Moved => {
+                if let Some(touch) = self.active.get_mut(&id) {
+                    touch.position = position;
+                    touch.phase = phase;
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                if let Some(mut touch) = self.active.remove(&id) {
+                    touch.position = position;
+                    touch.phase = phase;
+                    self.just_ended.push(touch);
+                }
+                self.just_started.retain(|started| *started != id);
+            }
+        }
+        let primary = self.primary == Some(id);
+        if primary && matches!(phase, TouchPhase::Ended | TouchPhase::Cancelled) {
+            self.primary = None;
+        }
+        primary
+    }
+
+    /// Clears the state that only lasts for a single frame.
+    pub fn clear(&mut self) {
+        self.just_started.clear();
+        self.just_ended.clear();
+        for touch in self.active.values_mut() {
+            touch.previous_position = touch.position;
+        }
+    }
+
+    /// Cancels all touches, e.g. when the window loses focus.
+    pub fn release_all(&mut self) {
+        for (_, mut touch) in self.active.drain() {
+            touch.phase = TouchPhase::Cancelled;
+            self.just_ended.push(touch);
+        }
+        self.just_started.clear();
+        self.primary = None;
+    }
+}
+
+#[test]
+fn test_touch_gestures() {
+    let mut touches = Touches::new();
+    assert!(touches.process(1, TouchPhase::Started, PhysicalPosition::new(0.0, 0.0)));
+    assert!(!touches.process(2, TouchPhase::Started, PhysicalPosition::new(10.0, 0.0)));
+    assert_eq!(touches.pinch(), Some(1.0));
+    touches.clear();
+
+    touches.process(1, TouchPhase::Moved, PhysicalPosition::new(0.0, 10.0));
+    touches.process(2, TouchPhase::Moved, PhysicalPosition::new(30.0, 10.0));
+    assert_eq!(touches.pinch(), Some(3.0));
+    assert_eq!(touches.two_finger_drag(), Some(Vec2::new(10.0, 10.0)));
+
+    touches.process(1, TouchPhase::Ended, PhysicalPosition::new(0.0, 10.0));
+    assert_eq!(touches.pinch(), None);
+    assert!(touches.primary().is_none());
+    assert_eq!(touches.iter_just_ended().count(), 1);
+    touches.clear();
+    assert_eq!(touches.iter_just_ended().count(), 0);
+    assert_eq!(touches.get(2).unwrap().delta(), Vec2::ZERO);
+}
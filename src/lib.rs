@@ -1,4 +1,5 @@
 pub mod app;
+pub mod assets;
 pub mod builtins;
 pub mod ecs;
 pub mod graphics;
@@ -6,15 +7,24 @@ pub mod input;
 pub mod render;
 
 pub mod prelude {
-    pub use crate::app::{App, SystemStage::*};
+    pub use crate::app::{App, AppExit, SystemStage::*};
     pub use crate::builtins::primitives::Primitive;
+    pub use crate::builtins::resources::time::{FixedTime, InterpolationAlpha, Time};
+    pub use crate::builtins::systems::hierarchy::{LocalTransform, Parent};
+    pub use crate::builtins::systems::render::MeshCheck;
+    pub use crate::builtins::systems::visibility::{CullingStats, Visibility};
     pub use crate::builtins::systems::ActiveCamera;
     pub use crate::ecs::{
         query::Query,
         resource::{Res, ResMut},
+        state::{in_state, NextState, OnEnter, OnExit, State, States},
+        system::IntoConditionalSystem,
         World,
     };
-    pub use crate::graphics::{Mesh, PerspectiveCamera, Transform};
-    pub use crate::input::InputState;
+    pub use crate::graphics::{
+        Aabb, Mesh, OrthographicCamera, PerspectiveCamera, Projection, Transform,
+    };
+    pub use crate::input::recording::{InputRecorder, InputReplay};
+    pub use crate::input::{Binding, ButtonInput, InputMap, InputState, TextInputEvent, Touches};
     pub use glam::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
 }
@@ -1,9 +1,10 @@
 // re-export renderer::Renderer
+pub use depth::DepthMode;
 pub use renderer::Renderer;
 
 pub mod context;
+pub mod depth;
 pub mod render_pass;
 pub mod renderer;
 pub mod texture;
 pub mod vertex;
-
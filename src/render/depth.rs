@@ -0,0 +1,49 @@
+/// How depth values are mapped, which the projections, pipelines, render passes and depth
+/// samplers all have to agree on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DepthMode {
+    /// The near plane maps to depth `0` and the far plane to `1`.
+    #[default]
+    Standard,
+    /// The near plane maps to depth `1` and an infinitely distant far plane to `0`.
+    ///
+    /// Floating-point depth has the most precision near `0`, so this spreads precision evenly
+    /// over the distance and avoids z-fighting in large scenes. Orthographic cameras keep their
+    /// far plane but are reversed as well.
+    ReverseZ,
+}
+
+impl DepthMode {
+    /// The depth of the near plane in normalized device coordinates.
+    pub fn near(&self) -> f32 {
+        match self {
+            DepthMode::Standard => 0.0,
+            DepthMode::ReverseZ => 1.0,
+        }
+    }
+
+    /// The value the depth buffer is cleared to, i.e. the depth of the far plane.
+    pub fn clear_value(&self) -> f32 {
+        match self {
+            DepthMode::Standard => 1.0,
+            DepthMode::ReverseZ => 0.0,
+        }
+    }
+
+    /// The depth test that lets closer fragments pass.
+    pub fn compare(&self) -> wgpu::CompareFunction {
+        match self {
+            DepthMode::Standard => wgpu::CompareFunction::Less,
+            DepthMode::ReverseZ => wgpu::CompareFunction::Greater,
+        }
+    }
+
+    /// Like [`DepthMode::compare`], but also passing equal depths, as used by comparison
+    /// samplers.
+    pub fn compare_equal(&self) -> wgpu::CompareFunction {
+        match self {
+            DepthMode::Standard => wgpu::CompareFunction::LessEqual,
+            DepthMode::ReverseZ => wgpu::CompareFunction::GreaterEqual,
+        }
+    }
+}
@@ -1,7 +1,11 @@
+use super::DepthMode;
+
 #[derive(Default)]
 pub struct RenderPassBuilder<'pass> {
     color_attachments: Vec<Option<wgpu::RenderPassColorAttachment<'pass>>>,
-    depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment<'pass>>,
+    /// The depth view and how it is loaded, where `None` clears it according to `depth_mode`.
+    depth_stencil_attachment: Option<(&'pass wgpu::TextureView, Option<wgpu::LoadOp<f32>>)>,
+    depth_mode: DepthMode,
 }
 
 impl<'pass> RenderPassBuilder<'pass> {
@@ -9,12 +13,23 @@ impl<'pass> RenderPassBuilder<'pass> {
         Self::default()
     }
 
-    pub fn color_attachment(mut self, view: &'pass wgpu::TextureView) -> Self {
+    /// Adds a color attachment that is cleared to transparent.
+    pub fn color_attachment(self, view: &'pass wgpu::TextureView) -> Self {
+        self.color_attachment_with_load(view, wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT))
+    }
+
+    /// Adds a color attachment, e.g. with [`wgpu::LoadOp::Load`] to draw on top of a previous
+    /// pass.
+    pub fn color_attachment_with_load(
+        mut self,
+        view: &'pass wgpu::TextureView,
+        load: wgpu::LoadOp<wgpu::Color>,
+    ) -> Self {
         self.color_attachments
             .push(Some(wgpu::RenderPassColorAttachment {
                 view,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    load,
                     store: wgpu::StoreOp::Store,
                 },
                 resolve_target: None,
@@ -22,15 +37,27 @@ impl<'pass> RenderPassBuilder<'pass> {
         self
     }
 
+    /// Sets the depth mode that determines the clear value of the depth attachment. Defaults to
+    /// [`DepthMode::Standard`].
+    pub fn depth_mode(mut self, depth_mode: DepthMode) -> Self {
+        self.depth_mode = depth_mode;
+        self
+    }
+
+    /// Sets a depth attachment that is cleared to the far plane.
     pub fn depth_stencil_attachment(mut self, view: &'pass wgpu::TextureView) -> Self {
-        self.depth_stencil_attachment = Some(wgpu::RenderPassDepthStencilAttachment {
-            view,
-            depth_ops: Some(wgpu::Operations {
-                load: wgpu::LoadOp::Clear(1.0),
-                store: wgpu::StoreOp::Store,
-            }),
-            stencil_ops: None,
-        });
+        self.depth_stencil_attachment = Some((view, None));
+        self
+    }
+
+    /// Sets a depth attachment, e.g. with [`wgpu::LoadOp::Load`] to draw on top of a previous
+    /// pass.
+    pub fn depth_stencil_attachment_with_load(
+        mut self,
+        view: &'pass wgpu::TextureView,
+        load: wgpu::LoadOp<f32>,
+    ) -> Self {
+        self.depth_stencil_attachment = Some((view, Some(load)));
         self
     }
 
@@ -42,7 +69,17 @@ impl<'pass> RenderPassBuilder<'pass> {
         let RenderPassBuilder {
             color_attachments,
             depth_stencil_attachment,
+            depth_mode,
         } = self;
+        let depth_stencil_attachment =
+            depth_stencil_attachment.map(|(view, load)| wgpu::RenderPassDepthStencilAttachment {
+                view,
+                depth_ops: Some(wgpu::Operations {
+                    load: load.unwrap_or(wgpu::LoadOp::Clear(depth_mode.clear_value())),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            });
         encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             color_attachments: &color_attachments,
             depth_stencil_attachment,
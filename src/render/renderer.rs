@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use winit::window::Window;
 
-use crate::render::context::RenderContext;
+use crate::render::{context::RenderContext, vertex::VertexLayout, DepthMode};
 
 use super::context;
 
@@ -10,6 +10,10 @@ pub struct Renderer<'a> {
     pub window: Arc<Window>,
     pub context: RenderContext<'a>,
     pub pipelines: HashMap<String, wgpu::RenderPipeline>,
+    /// The vertex layouts of the pipelines that draw meshes, by pipeline name.
+    pub vertex_layouts: HashMap<String, VertexLayout>,
+    /// The depth mapping used by the built-in pipelines, set before they are created.
+    pub depth_mode: DepthMode,
 }
 
 impl<'a> Renderer<'a> {
@@ -19,6 +23,8 @@ impl<'a> Renderer<'a> {
             window,
             context,
             pipelines: Default::default(),
+            vertex_layouts: Default::default(),
+            depth_mode: DepthMode::default(),
         }
     }
 
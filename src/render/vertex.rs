@@ -1,3 +1,158 @@
-pub trait Vertex {
-    fn desc() -> wgpu::VertexBufferLayout<'static>;
+use thiserror::Error;
+use wgpu::naga;
+
+use crate::graphics::mesh::MeshAttribute;
+
+#[derive(Debug, Error)]
+pub enum VertexLayoutError {
+    #[error("Failed to parse shader: {0}")]
+    Parse(#[from] naga::front::wgsl::ParseError),
+    #[error("No vertex entry point `{0}`")]
+    MissingEntryPoint(String),
+    #[error("Vertex input `{0}` is not a known mesh attribute")]
+    UnknownAttribute(String),
+}
+
+/// The layout of an interleaved vertex buffer: which [`MeshAttribute`]s a pipeline reads, at
+/// which shader locations and offsets.
+///
+/// Meshes are converted into this layout with [`Mesh::vertex_buffer_data`].
+///
+/// [`Mesh::vertex_buffer_data`]: crate::graphics::Mesh::vertex_buffer_data
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexLayout {
+    mesh_attributes: Vec<(MeshAttribute, u64)>,
+    attributes: Vec<wgpu::VertexAttribute>,
+    array_stride: u64,
+}
+
+impl VertexLayout {
+    /// Packs the `attributes` tightly in the given order, each read from its shader location.
+    pub fn new(attributes: impl IntoIterator<Item = (MeshAttribute, u32)>) -> Self {
+        let mut layout = Self {
+            mesh_attributes: Vec::new(),
+            attributes: Vec::new(),
+            array_stride: 0,
+        };
+        for (attribute, shader_location) in attributes {
+            layout.attributes.push(wgpu::VertexAttribute {
+                format: attribute.format,
+                offset: layout.array_stride,
+                shader_location,
+            });
+            layout
+                .mesh_attributes
+                .push((attribute, layout.array_stride));
+            layout.array_stride += attribute.format.size();
+        }
+        layout
+    }
+
+    /// The layout of the inputs of the vertex shader `entry_point`. Each `@location` input is
+    /// matched by name with one of `attributes`, e.g. `@location(1) normal: vec3<f32>` with
+    /// [`MeshAttribute::NORMAL`].
+    pub fn from_wgsl(
+        source: &str,
+        entry_point: &str,
+        attributes: &[MeshAttribute],
+    ) -> Result<Self, VertexLayoutError> {
+        let module = naga::front::wgsl::parse_str(source)?;
+        let function = &module
+            .entry_points
+            .iter()
+            .find(|entry| entry.stage == naga::ShaderStage::Vertex && entry.name == entry_point)
+            .ok_or_else(|| VertexLayoutError::MissingEntryPoint(entry_point.to_string()))?
+            .function;
+
+        // Inputs are either arguments with a binding or members of struct arguments
+        let mut inputs = Vec::new();
+        for argument in &function.arguments {
+            match &module.types[argument.ty].inner {
+                naga::TypeInner::Struct { members, .. } => inputs.extend(
+                    members
+                        .iter()
+                        .map(|member| (member.name.as_deref(), member.binding.as_ref())),
+                ),
+                _ => inputs.push((argument.name.as_deref(), argument.binding.as_ref())),
+            }
+        }
+
+        let mut located = inputs
+            .into_iter()
+            .filter_map(|(name, binding)| match binding {
+                Some(naga::Binding::Location { location, .. }) => {
+                    Some((name.unwrap_or_default(), *location))
+                }
+                _ => None,
+            })
+            .map(|(name, location)| {
+                attributes
+                    .iter()
+                    .find(|attribute| attribute.name == name)
+                    .map(|attribute| (*attribute, location))
+                    .ok_or_else(|| VertexLayoutError::UnknownAttribute(name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        located.sort_by_key(|(_, location)| *location);
+        Ok(Self::new(located))
+    }
+
+    /// The attributes with their byte offsets within a vertex.
+    pub fn mesh_attributes(&self) -> &[(MeshAttribute, u64)] {
+        &self.mesh_attributes
+    }
+
+    pub fn array_stride(&self) -> u64 {
+        self.array_stride
+    }
+
+    pub fn buffer_layout(&self) -> wgpu::VertexBufferLayout<'_> {
+        wgpu::VertexBufferLayout {
+            array_stride: self.array_stride,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &self.attributes,
+        }
+    }
+}
+
+#[test]
+fn test_vertex_layout() {
+    use crate::graphics::mesh::{Mesh, MeshError, MeshVertex};
+
+    let layout = VertexLayout::from_wgsl(
+        include_str!("../builtins/shaders/scene.wgsl"),
+        "vs_main",
+        &MeshAttribute::BUILTIN,
+    )
+    .unwrap();
+    assert_eq!(
+        layout.mesh_attributes(),
+        [
+            (MeshAttribute::POSITION, 0),
+            (MeshAttribute::NORMAL, 12),
+            (MeshAttribute::TEX_COORDS, 24)
+        ]
+    );
+    assert_eq!(layout.buffer_layout().attributes[2].shader_location, 2);
+
+    let vertex = MeshVertex {
+        position: [1.0, 2.0, 3.0],
+        normal: [0.0, 1.0, 0.0],
+        tex_coords: [0.5, 0.25],
+    };
+    let mut mesh = Mesh::from_vertices(&[vertex; 2], Vec::new());
+    let data = mesh.vertex_buffer_data(&layout).unwrap();
+    assert_eq!(data.len(), 2 * 32);
+    assert_eq!(
+        bytemuck::pod_collect_to_vec::<u8, f32>(&data[32..]),
+        [1.0, 2.0, 3.0, 0.0, 1.0, 0.0, 0.5, 0.25]
+    );
+
+    let colored = VertexLayout::new([(MeshAttribute::POSITION, 0), (MeshAttribute::COLOR, 3)]);
+    assert_eq!(
+        mesh.vertex_buffer_data(&colored),
+        Err(MeshError::MissingAttribute("color"))
+    );
+    mesh.insert_attribute(MeshAttribute::COLOR, vec![[1.0; 4]; 2]);
+    assert_eq!(mesh.vertex_buffer_data(&colored).unwrap().len(), 2 * 28);
 }